@@ -0,0 +1,13 @@
+/// A color written `#rrggbb`.
+pub fn parse_color(color: &str) -> Result<[u8; 3], String> {
+    let color = color.trim();
+    let hex = color
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.is_ascii())
+        .ok_or_else(|| format!("expected a color like `#3d67a6`, got `{}`", color))?;
+    let mut rgb = [0; 3];
+    for (i, channel) in rgb.iter_mut().enumerate() {
+        *channel = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| format!("invalid color `{}`", color))?;
+    }
+    Ok(rgb)
+}
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::HtmlCanvasElement;
+use web_sys::{HtmlCanvasElement, HtmlInputElement};
 
 #[allow(dead_code)]
 #[wasm_bindgen]
@@ -27,6 +27,15 @@ pub fn canvas(id: &str) -> HtmlCanvasElement {
         .unwrap()
 }
 
+pub fn input(id: &str) -> HtmlInputElement {
+    document()
+        .get_element_by_id(id)
+        .unwrap()
+        .dyn_into::<HtmlInputElement>()
+        .map_err(|_| ())
+        .unwrap()
+}
+
 pub fn canvas_context<T: JsCast>(canvas: &HtmlCanvasElement, ctx: &str) -> T {
     canvas
         .get_context_with_context_options(ctx, &(ContextOptions { alpha: false }).into())
@@ -83,6 +92,39 @@ where
     closure.forget();
 }
 
+pub fn add_event_listener<F>(target: &web_sys::EventTarget, event: &str, callback: F)
+where
+    F: Fn(web_sys::Event) + 'static,
+{
+    let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(_)>);
+    target
+        .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
+pub fn add_wheel_event_listener<F>(canvas: &HtmlCanvasElement, callback: F)
+where
+    F: Fn(web_sys::WheelEvent) + 'static,
+{
+    let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(_)>);
+    canvas
+        .add_event_listener_with_callback("wheel", closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
+pub fn add_keyboard_event_listener<F>(event: &str, callback: F)
+where
+    F: Fn(web_sys::KeyboardEvent) + 'static,
+{
+    let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(_)>);
+    window()
+        .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+        .unwrap();
+    closure.forget();
+}
+
 pub fn resize_canvas_to_window_size(canvas_id: &'static str) {
     let closure = Closure::wrap(Box::new(move || {
         let c = canvas(canvas_id);
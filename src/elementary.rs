@@ -0,0 +1,260 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+use crate::game_of_life::{Automaton, Cell, Engine, EngineKind, GameOfLife, Random, Rule, Topology};
+
+/// A Wolfram rule of an elementary cellular automaton, see https://mathworld.wolfram.com/ElementaryCellularAutomaton.html
+///
+/// Bit `4 * left + 2 * center + right` of the rule number is the next state of a cell from its state and
+/// the states of its left and right neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementaryRule(pub u8);
+
+impl ElementaryRule {
+    pub fn new(number: u32) -> Result<ElementaryRule, String> {
+        if number > 255 {
+            return Err(format!("an elementary rule is a number from 0 to 255, got {}", number));
+        }
+        Ok(ElementaryRule(number as u8))
+    }
+
+    pub fn next(&self, left: u8, center: u8, right: u8) -> u8 {
+        self.0 >> (left << 2 | center << 1 | right) & 1
+    }
+}
+
+impl fmt::Display for ElementaryRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rule {}", self.0)
+    }
+}
+
+/// Every row of a 2D grid would follow the rule on its own, from its west and east neighbours.
+impl Automaton for ElementaryRule {
+    fn states(&self) -> u8 {
+        2
+    }
+
+    fn next(&self, state: u8, neighbors: &[u8; 8]) -> u8 {
+        ElementaryRule::next(self, neighbors[3], state, neighbors[4])
+    }
+
+    fn colors(&self) -> Vec<[u8; 3]> {
+        vec![[255, 255, 255], [0, 0, 0]]
+    }
+}
+
+/// The first generation of an elementary automaton.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Seed {
+    /// A single live cell in the middle.
+    Center,
+    /// Every cell is alive with a probability of one half.
+    Random,
+    /// Cells from a string of `0` and `1`, in the middle.
+    Bits(Vec<u8>),
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "center" => Ok(Seed::Center),
+            "random" => Ok(Seed::Random),
+            bits if !bits.is_empty() && bits.chars().all(|c| c == '0' || c == '1') => {
+                Ok(Seed::Bits(bits.bytes().map(|bit| bit - b'0').collect()))
+            }
+            _ => Err(format!(
+                "unknown seed `{}`, expected `center`, `random` or a string of `0` and `1`",
+                s
+            )),
+        }
+    }
+}
+
+/// An elementary cellular automaton on a row of cells, drawn as a space-time diagram.
+///
+/// Row `i` of the engine is the `i`th of the last `rows` generations, the newest one at the bottom, so
+/// the view scrolls up as generations are added. Only the newest generation can be edited. The edges of
+/// the row are dead on a plane and joined on a torus.
+#[derive(Clone)]
+pub struct Elementary {
+    pub width: u32,
+    pub rows: u32,
+    generations: VecDeque<Vec<u8>>,
+    rule: ElementaryRule,
+    topology: Topology,
+    pub generation: u64,
+}
+
+impl Elementary {
+    pub fn new(rule: ElementaryRule, width: u32, rows: u32, seed: &Seed, random: &mut Random) -> Elementary {
+        let mut cells = vec![0; width as usize];
+        match seed {
+            Seed::Center => {
+                if let Some(cell) = cells.get_mut(width as usize / 2) {
+                    *cell = 1;
+                }
+            }
+            Seed::Random => cells
+                .iter_mut()
+                .for_each(|cell| *cell = (random.next_f64() < 0.5) as u8),
+            Seed::Bits(bits) => {
+                let left = (width as usize).saturating_sub(bits.len()) / 2;
+                cells[left..]
+                    .iter_mut()
+                    .zip(bits.iter())
+                    .for_each(|(cell, bit)| *cell = *bit);
+            }
+        }
+        Elementary {
+            width,
+            rows: rows.max(1),
+            generations: vec![cells].into(),
+            rule,
+            topology: Topology::default(),
+            generation: 0,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let cells = self.generations.back().unwrap();
+        let width = cells.len();
+        let wrap = self.topology == Topology::Torus;
+        let cell = |i: isize| match i {
+            -1 if wrap => cells[width - 1],
+            i if i as usize == width && wrap => cells[0],
+            i if i < 0 || i as usize >= width => 0,
+            i => cells[i as usize],
+        };
+        let next = (0..width as isize)
+            .map(|i| self.rule.next(cell(i - 1), cell(i), cell(i + 1)))
+            .collect();
+        if self.generations.len() == self.rows as usize {
+            self.generations.pop_front();
+        }
+        self.generations.push_back(next);
+        self.generation += 1;
+    }
+
+    fn contains(&self, row: i64, column: i64) -> bool {
+        row >= 0 && column >= 0 && row < self.generations.len() as i64 && column < self.width as i64
+    }
+}
+
+impl Engine for Elementary {
+    /// Elementary automata do not follow a Life-like rule, this is Conway's rule.
+    fn rule(&self) -> Rule {
+        Rule::default()
+    }
+
+    fn set_rule(&mut self, _rule: Rule) -> Result<(), String> {
+        Err(String::from("elementary automata do not follow Life-like rules"))
+    }
+
+    fn automaton(&self) -> Rc<dyn Automaton> {
+        Rc::new(self.rule)
+    }
+
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        match topology {
+            Topology::Plane | Topology::Torus => {
+                self.topology = topology;
+                Ok(())
+            }
+            _ => Err(format!(
+                "the row of an elementary automaton is a plane or a torus, not {:?}",
+                topology
+            )),
+        }
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.generations
+            .iter()
+            .map(|cells| cells.iter().map(|cell| *cell as u64).sum::<u64>())
+            .sum()
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        (0..1u64 << k).for_each(|_| self.tick());
+    }
+
+    fn cell(&self, row: i64, column: i64) -> Cell {
+        if self.contains(row, column) && self.generations[row as usize][column as usize] != 0 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell) {
+        // Older generations are history, changing them would not change the newest one
+        if self.contains(row, column) && row as usize == self.generations.len() - 1 {
+            self.generations[row as usize][column as usize] = cell as u8;
+        }
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut live_cells = vec![];
+        for (row, cells) in self.generations.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                if *cell != 0 {
+                    live_cells.push((row as i64, column as i64));
+                }
+            }
+        }
+        live_cells
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.generations.len() * self.width as usize
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        Some((0, 0, self.generations.len() as i64 - 1, self.width as i64 - 1))
+    }
+
+    /// All rows, so that the view does not jump as it scrolls.
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        Some((0, 0, self.rows as i64 - 1, self.width as i64 - 1))
+    }
+}
+
+/// Runs an elementary automaton on the Life canvas, `width` cells wide with the last `rows` generations
+/// shown. The seed is `center`, `random` or a string of `0` and `1`.
+#[wasm_bindgen]
+pub fn elementary(
+    rule: u32,
+    seed: Option<String>,
+    width: Option<u32>,
+    rows: Option<u32>,
+    renderer: Option<String>,
+) -> Result<GameOfLife, JsValue> {
+    let seed = seed.as_deref().unwrap_or("center").parse()?;
+    let mut random = Random::new(js_sys::Date::now() as u64);
+    let engine = Elementary::new(
+        ElementaryRule::new(rule)?,
+        width.unwrap_or(256),
+        rows.unwrap_or(128),
+        &seed,
+        &mut random,
+    );
+    GameOfLife::start(Box::new(engine), EngineKind::Dense, renderer)
+}
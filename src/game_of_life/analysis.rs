@@ -0,0 +1,199 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hasher;
+
+use crate::game_of_life::{Cell, Engine, Fnv1a, Rule, SparseUniverse};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Classification {
+    Empty,
+    StillLife,
+    Oscillator,
+    Spaceship,
+}
+
+/// A pattern that repeats itself every `period` generations from `generation` on, moved by
+/// `displacement` `(rows, columns)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Periodicity {
+    pub generation: u64,
+    pub period: u64,
+    pub displacement: (i64, i64),
+    pub population: u64,
+}
+
+impl Periodicity {
+    pub fn classification(&self) -> Classification {
+        if self.population == 0 {
+            Classification::Empty
+        } else if self.displacement != (0, 0) {
+            Classification::Spaceship
+        } else if self.period == 1 {
+            Classification::StillLife
+        } else {
+            Classification::Oscillator
+        }
+    }
+}
+
+impl fmt::Display for Periodicity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.classification() {
+            Classification::Empty => write!(f, "empty"),
+            Classification::StillLife => write!(f, "still life"),
+            Classification::Oscillator => write!(f, "period {} oscillator", self.period),
+            Classification::Spaceship => write!(
+                f,
+                "period {} spaceship moving ({}, {})",
+                self.period, self.displacement.0, self.displacement.1
+            ),
+        }
+    }
+}
+
+/// One observed generation, the cells and their states are only kept as the hash of their normalized
+/// form.
+struct State {
+    generation: u64,
+    hash: u64,
+    population: u64,
+    offset: (i64, i64),
+}
+
+/// Finds the first generation that repeats an earlier one, up to a translation.
+///
+/// Generations have to be observed one after another, a state is compared to the `max_period` states
+/// before it by the hash of its normalized cells.
+pub struct PeriodDetector {
+    max_period: u64,
+    states: VecDeque<State>,
+}
+
+impl PeriodDetector {
+    pub fn new(max_period: u64) -> PeriodDetector {
+        PeriodDetector {
+            max_period,
+            states: VecDeque::new(),
+        }
+    }
+
+    /// Observes the current generation of the engine.
+    pub fn observe(&mut self, engine: &dyn Engine) -> Option<Periodicity> {
+        self.observe_cells(engine.generation(), engine.cell_states())
+    }
+
+    pub fn observe_cells(&mut self, generation: u64, cells: Vec<((i64, i64), u8)>) -> Option<Periodicity> {
+        let population = cells.len() as u64;
+        let offset = normalize(cells.iter().map(|(cell, _)| *cell).collect()).1;
+        let mut cells: Vec<_> = cells
+            .into_iter()
+            .map(|((row, col), state)| ((row - offset.0, col - offset.1), state))
+            .collect();
+        cells.sort_unstable();
+        let hash = hash_cells(&cells);
+        let periodicity = self
+            .states
+            .iter()
+            .rev()
+            .find(|state| state.hash == hash && state.population == population)
+            .map(|state| Periodicity {
+                generation: state.generation,
+                period: generation - state.generation,
+                displacement: (offset.0 - state.offset.0, offset.1 - state.offset.1),
+                population,
+            });
+
+        self.states.push_back(State {
+            generation,
+            hash,
+            population,
+            offset,
+        });
+        while self
+            .states
+            .front()
+            .is_some_and(|state| state.generation + self.max_period < generation)
+        {
+            self.states.pop_front();
+        }
+        periodicity
+    }
+}
+
+/// Steps the engine one generation at a time until it repeats, for at most `max_generations`.
+pub fn find_period(engine: &mut dyn Engine, max_generations: u64, max_period: u64) -> Option<Periodicity> {
+    let mut detector = PeriodDetector::new(max_period);
+    let last = engine.generation() + max_generations;
+    loop {
+        if let Some(periodicity) = detector.observe(engine) {
+            return Some(periodicity);
+        }
+        if engine.generation() >= last {
+            return None;
+        }
+        engine.step_pow2(0);
+    }
+}
+
+/// Runs the cells alone on an unbounded plane until they repeat.
+pub fn analyze_object(
+    cells: &[(i64, i64)],
+    rule: Rule,
+    max_generations: u64,
+    max_period: u64,
+) -> Result<Option<Periodicity>, String> {
+    let mut universe = SparseUniverse::new(rule)?;
+    cells
+        .iter()
+        .for_each(|(row, col)| universe.set_cell(*row, *col, Cell::Alive));
+    Ok(find_period(&mut universe, max_generations, max_period))
+}
+
+/// Moves the cells so that their bounding box starts at `(0, 0)` and sorts them, returns the
+/// `(top, left)` they were moved from.
+pub fn normalize(mut cells: Vec<(i64, i64)>) -> (Vec<(i64, i64)>, (i64, i64)) {
+    let top = cells.iter().map(|(row, _)| *row).min().unwrap_or(0);
+    let left = cells.iter().map(|(_, col)| *col).min().unwrap_or(0);
+    cells.iter_mut().for_each(|(row, col)| {
+        *row -= top;
+        *col -= left;
+    });
+    cells.sort_unstable();
+    (cells, (top, left))
+}
+
+/// The hash of sorted cells and their states, stable across runs and platforms.
+pub fn hash_cells(cells: &[((i64, i64), u8)]) -> u64 {
+    let mut hasher = Fnv1a::default();
+    for ((row, col), state) in cells {
+        hasher.write_i64(*row);
+        hasher.write_i64(*col);
+        hasher.write_u8(*state);
+    }
+    hasher.finish()
+}
+
+/// Splits live cells into groups of cells that touch, including diagonally.
+pub fn objects(cells: &[(i64, i64)]) -> Vec<Vec<(i64, i64)>> {
+    let mut remaining: HashSet<(i64, i64)> = cells.iter().copied().collect();
+    let mut objects = vec![];
+    for cell in cells {
+        if !remaining.remove(cell) {
+            continue;
+        }
+        let mut object = vec![*cell];
+        let mut i = 0;
+        while let Some(&(row, col)) = object.get(i) {
+            for neighbor_row in row - 1..=row + 1 {
+                for neighbor_col in col - 1..=col + 1 {
+                    if remaining.remove(&(neighbor_row, neighbor_col)) {
+                        object.push((neighbor_row, neighbor_col));
+                    }
+                }
+            }
+            i += 1;
+        }
+        objects.push(object);
+    }
+    objects
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::game_of_life::Rule;
+
+/// The rule of a cellular automaton with any number of states, on the 8 neighbours of a cell.
+///
+/// State 0 is dead, every other state is drawn with its own color, see `MultiStateUniverse`.
+pub trait Automaton: fmt::Display {
+    fn states(&self) -> u8;
+
+    /// The next state of a cell from its state and the states of its 8 neighbours.
+    fn next(&self, state: u8, neighbors: &[u8; 8]) -> u8;
+
+    /// The color of every state, the dead color first.
+    fn colors(&self) -> Vec<[u8; 3]>;
+
+    /// The state of cells that are set alive, like pasted or filled cells.
+    fn live_state(&self) -> u8 {
+        1
+    }
+
+    /// The rule the cells in state 1 follow, if they follow a Life-like one.
+    fn life_rule(&self) -> Option<Rule> {
+        None
+    }
+}
+
+const DEAD_COLOR: [u8; 3] = [255, 255, 255];
+const ALIVE_COLOR: [u8; 3] = [0, 0, 0];
+
+impl Automaton for Rule {
+    fn states(&self) -> u8 {
+        2
+    }
+
+    fn next(&self, state: u8, neighbors: &[u8; 8]) -> u8 {
+        let live_neighbors = neighbors.iter().filter(|state| **state == 1).count() as u8;
+        let set = if state == 0 { self.birth } else { self.survival };
+        (set & (1 << live_neighbors) != 0) as u8
+    }
+
+    fn colors(&self) -> Vec<[u8; 3]> {
+        vec![DEAD_COLOR, ALIVE_COLOR]
+    }
+
+    fn life_rule(&self) -> Option<Rule> {
+        Some(*self)
+    }
+}
+
+/// A rule of the Generations family, see https://conwaylife.com/wiki/Generations
+///
+/// Live cells follow the Life-like rule, but instead of dying at once they go through the dying states
+/// `2..states` one generation at a time, and only state 1 counts as a live neighbour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Generations {
+    pub rule: Rule,
+    pub states: u8,
+}
+
+impl Generations {
+    /// Parses `S/B/C` notation like `/2/3` for Brian's Brain, or `B2/S/C3`.
+    pub fn parse(s: &str) -> Result<Generations, String> {
+        let invalid = || format!("invalid Generations rule `{}`, expected `/2/3` or `B2/S/C3`", s);
+        let parts: Vec<&str> = s.trim().split('/').map(str::trim).collect();
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let prefixed = |prefix: char| {
+            parts
+                .iter()
+                .find(|part| part.starts_with(prefix) || part.starts_with(prefix.to_ascii_lowercase()))
+                .map(|part| &part[1..])
+        };
+        let (survival, birth, states) = match (prefixed('S'), prefixed('B'), prefixed('C').or(prefixed('G'))) {
+            (Some(survival), Some(birth), Some(states)) => (survival, birth, states),
+            (None, None, None) => (parts[0], parts[1], parts[2]),
+            _ => return Err(invalid()),
+        };
+        let rule: Rule = format!("B{}/S{}", birth, survival).parse()?;
+        let states = match states.parse() {
+            Ok(states) if states >= 2 => states,
+            _ => return Err(format!("invalid number of states `{}` in rule `{}`", states, s)),
+        };
+        Ok(Generations { rule, states })
+    }
+}
+
+impl Automaton for Generations {
+    fn states(&self) -> u8 {
+        self.states
+    }
+
+    fn next(&self, state: u8, neighbors: &[u8; 8]) -> u8 {
+        match state {
+            0 | 1 => match Automaton::next(&self.rule, state, neighbors) {
+                // A live cell that does not survive starts dying
+                0 if state == 1 => 2 % self.states,
+                next => next,
+            },
+            dying => (dying + 1) % self.states,
+        }
+    }
+
+    /// Dying cells fade from blue to light blue.
+    fn colors(&self) -> Vec<[u8; 3]> {
+        let (from, to) = ([61, 103, 166], [200, 215, 240]);
+        let dying = self.states as usize - 2;
+        let mut colors = vec![DEAD_COLOR, ALIVE_COLOR];
+        colors.extend((0..dying).map(|i| {
+            let t = if dying > 1 { i as f32 / (dying - 1) as f32 } else { 0.0 };
+            let mut color = [0; 3];
+            (0..3).for_each(|c| color[c] = (from[c] as f32 + (to[c] as f32 - from[c] as f32) * t).round() as u8);
+            color
+        }));
+        colors
+    }
+
+    fn life_rule(&self) -> Option<Rule> {
+        Some(self.rule)
+    }
+}
+
+impl fmt::Display for Generations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = self.rule.to_string();
+        let (birth, survival) = rule.split_once('/').unwrap();
+        write!(f, "{}/{}/{}", &survival[1..], &birth[1..], self.states)
+    }
+}
+
+/// Wireworld, see https://conwaylife.com/wiki/Wireworld
+///
+/// The states are numbered like in Golly: 1 is an electron head, 2 an electron tail and 3 a conductor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wireworld;
+
+impl Wireworld {
+    pub const HEAD: u8 = 1;
+    pub const TAIL: u8 = 2;
+    pub const CONDUCTOR: u8 = 3;
+}
+
+impl Automaton for Wireworld {
+    fn states(&self) -> u8 {
+        4
+    }
+
+    fn next(&self, state: u8, neighbors: &[u8; 8]) -> u8 {
+        match state {
+            Wireworld::HEAD => Wireworld::TAIL,
+            Wireworld::TAIL => Wireworld::CONDUCTOR,
+            Wireworld::CONDUCTOR => {
+                let heads = neighbors.iter().filter(|state| **state == Wireworld::HEAD).count();
+                if heads == 1 || heads == 2 {
+                    Wireworld::HEAD
+                } else {
+                    Wireworld::CONDUCTOR
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    fn colors(&self) -> Vec<[u8; 3]> {
+        vec![[0, 0, 0], [0, 128, 255], [255, 255, 255], [255, 128, 0]]
+    }
+
+    /// New cells are conductors, electrons are added by clicking them.
+    fn live_state(&self) -> u8 {
+        Wireworld::CONDUCTOR
+    }
+}
+
+impl fmt::Display for Wireworld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Wireworld")
+    }
+}
+
+/// Parses `Wireworld`, a Generations rule like `/2/3`, or a Life-like rule like `B3/S23`.
+pub fn parse_automaton(s: &str) -> Result<Rc<dyn Automaton>, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("wireworld") {
+        Ok(Rc::new(Wireworld))
+    } else if s.matches('/').count() == 2 {
+        Ok(Rc::new(Generations::parse(s)?))
+    } else {
+        Ok(Rc::new(s.parse::<Rule>()?))
+    }
+}
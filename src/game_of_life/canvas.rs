@@ -0,0 +1,205 @@
+use crate::dom;
+use crate::game_of_life::{
+    advance, Blocks, CellStats, ColorMode, ColorScheme, Editor, Engine, History, Viewport, DEFAULT_MEMORY_LIMIT,
+    KEYFRAME_INTERVAL,
+};
+
+/// The view of an engine on the page, drawn by a `Renderer`.
+pub struct Canvas {
+    pub viewport: Viewport,
+    /// Draws the lines between cells when they are large enough.
+    pub grid: bool,
+    /// Draws the highlights of the engine, like the positions of turmites.
+    pub highlight: bool,
+    pub color_mode: ColorMode,
+    pub color_scheme: ColorScheme,
+    /// Only kept up to date when the color mode needs it.
+    pub stats: CellStats,
+    pub engine: Box<dyn Engine>,
+    pub editor: Editor,
+    pub history: History,
+    /// The cells were edited since the last step, so the history after it is outdated.
+    pub edited: bool,
+    pub paused: bool,
+    /// Every step advances the engine by `2^step_exponent` generations.
+    pub step_exponent: u32,
+    /// The last mouse position while dragging the view.
+    pub pan: Option<(i32, i32)>,
+}
+
+impl Canvas {
+    pub fn new(engine: Box<dyn Engine>) -> Canvas {
+        let canvas = dom::canvas("canvas");
+        let viewport = Viewport::new(canvas.client_width() as u32, canvas.client_height() as u32, 20.0);
+
+        Canvas {
+            viewport,
+            grid: true,
+            highlight: true,
+            color_mode: ColorMode::Plain,
+            color_scheme: ColorScheme::default(),
+            stats: CellStats::default(),
+            engine,
+            editor: Editor::new(js_sys::Date::now() as u64),
+            history: History::new(KEYFRAME_INTERVAL, DEFAULT_MEMORY_LIMIT),
+            edited: true,
+            paused: false,
+            step_exponent: 0,
+            pan: None,
+        }
+    }
+
+    /// The `(row, column)` of the cell at the given pixel offset.
+    pub fn cell_at(&self, x: i32, y: i32) -> (i64, i64) {
+        self.viewport.cell_at(x as f64, y as f64)
+    }
+
+    /// Zooms and pans to show all live cells.
+    pub fn fit(&mut self) {
+        if let Some(bounding_box) = self.engine.bounding_box() {
+            self.viewport.fit(bounding_box);
+        }
+    }
+
+    pub fn update_stats(&mut self) {
+        if self.color_mode != ColorMode::Plain {
+            self.stats.update(self.engine.as_ref());
+        } else {
+            // Start over when a mode is picked again, instead of from stale cells
+            self.stats = CellStats::default();
+        }
+    }
+
+    /// Fills `texels` with the shade of every block of the viewport, row by row, the brightest shade of
+    /// its cells, or 0 if they are all drawn as dead.
+    pub fn blocks(&self, texels: &mut Vec<u8>) -> Blocks {
+        let blocks = self.viewport.blocks();
+        let (rows, columns) = (blocks.rows as i64, blocks.columns as i64);
+        texels.clear();
+        texels.resize((rows * columns) as usize, 0);
+        let mut shade_cell = |(row, column): (i64, i64), shade: u8| {
+            let row = row.div_euclid(blocks.size) - blocks.top;
+            let column = column.div_euclid(blocks.size) - blocks.left;
+            if (0..rows).contains(&row) && (0..columns).contains(&column) {
+                let texel = &mut texels[(row * columns + column) as usize];
+                *texel = (*texel).max(shade);
+            }
+        };
+
+        // Multi-state cells have the shade of their state, two-state cells the last shade
+        let states = self.engine.automaton().states();
+        let shade = match states {
+            2 => |_| 255,
+            _ => |state| state,
+        };
+        if self.color_mode != ColorMode::Plain {
+            for (cell, shade) in self.stats.shades(self.color_mode) {
+                shade_cell(cell, shade);
+            }
+        } else if blocks.size == 1 && self.engine.population() > (rows * columns) as u64 {
+            // Looking up every visible cell is faster when most of the live cells are visible
+            for i in 0..rows * columns {
+                let cell = (blocks.top + i / columns, blocks.left + i % columns);
+                let state = self.engine.state(cell.0, cell.1);
+                if state != 0 {
+                    shade_cell(cell, shade(state));
+                }
+            }
+        } else if blocks.size > 1 && states == 2 && self.engine.population() > (rows * columns) as u64 {
+            // Asking every block is faster than going through more live cells than there are blocks
+            for i in 0..rows * columns {
+                let (row, column) = (
+                    (blocks.top + i / columns) * blocks.size,
+                    (blocks.left + i % columns) * blocks.size,
+                );
+                if self
+                    .engine
+                    .any_alive((row, column, row + blocks.size - 1, column + blocks.size - 1))
+                {
+                    texels[i as usize] = 255;
+                }
+            }
+        } else {
+            for (cell, state) in self.engine.cell_states() {
+                shade_cell(cell, shade(state));
+            }
+        }
+        blocks
+    }
+
+    /// Fills `texels` with 255 for every block of `blocks` with a highlighted cell, and 0 for the others.
+    pub fn highlights(&self, blocks: &Blocks, texels: &mut Vec<u8>) {
+        let (rows, columns) = (blocks.rows as i64, blocks.columns as i64);
+        texels.clear();
+        texels.resize((rows * columns) as usize, 0);
+        if !self.highlight {
+            return;
+        }
+        for (row, column) in self.engine.highlights() {
+            let row = row.div_euclid(blocks.size) - blocks.top;
+            let column = column.div_euclid(blocks.size) - blocks.left;
+            if (0..rows).contains(&row) && (0..columns).contains(&column) {
+                texels[(row * columns + column) as usize] = 255;
+            }
+        }
+    }
+
+    /// The color of every shade, the colors of the states of multi-state automata or the color scheme.
+    pub fn palette(&self) -> Vec<[u8; 3]> {
+        let automaton = self.engine.automaton();
+        if self.color_mode != ColorMode::Plain || automaton.states() == 2 {
+            return self.color_scheme.palette();
+        }
+        let mut palette = automaton.colors();
+        palette.resize(256, palette[0]);
+        palette
+    }
+
+    /// The `(x, y, width, height)` in pixels of the selection.
+    pub fn selection_rect(&self) -> Option<(f64, f64, f64, f64)> {
+        self.editor.selection.map(|(top, left, bottom, right)| {
+            let (x0, y0) = self.viewport.pixel_at((top, left));
+            let (x1, y1) = self.viewport.pixel_at((bottom + 1, right + 1));
+            (x0, y0, x1 - x0, y1 - y0)
+        })
+    }
+
+    pub fn step(&mut self) {
+        if !self.paused {
+            self.step_forward();
+        }
+    }
+
+    pub fn step_forward(&mut self) {
+        self.update_history();
+        self.engine.step_pow2(self.step_exponent);
+        self.history.record(self.engine.as_ref());
+    }
+
+    /// Advances by any number of generations at once, without the steps in between.
+    pub fn fast_forward(&mut self, generations: u64) {
+        self.update_history();
+        advance(self.engine.as_mut(), generations);
+        self.history.record(self.engine.as_ref());
+    }
+
+    pub fn step_back(&mut self) {
+        let generation = self.engine.generation().saturating_sub(1 << self.step_exponent);
+        self.seek(generation);
+    }
+
+    /// Goes to the given generation, replaying from the history if it is in the past.
+    pub fn seek(&mut self, generation: u64) {
+        self.update_history();
+        if let Some(engine) = self.history.seek(generation) {
+            self.engine = engine;
+        }
+    }
+
+    fn update_history(&mut self) {
+        if self.edited {
+            self.history.reset(self.engine.as_ref());
+            self.edited = false;
+        }
+    }
+}
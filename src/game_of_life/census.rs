@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::game_of_life::{
+    find_period, normalize, objects, soup, soup_seed, Cell, Classification, Engine, Random, Rule, SparseUniverse,
+    Symmetry,
+};
+
+pub const SOUP_SIZE: u32 = 16;
+pub const SOUP_DENSITY: f64 = 0.5;
+/// Soups that are still changing after this many generations are counted as unstable.
+const MAX_GENERATIONS: u64 = 20_000;
+/// The longest period of the population that is considered stable.
+const MAX_POPULATION_PERIOD: usize = 30;
+/// The longest period of an object, longer or chaotic objects are counted as unknown.
+const MAX_OBJECT_GENERATIONS: u64 = 2_000;
+
+const WECHSLER_DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// A census of the objects left by random soups, like apgsearch, see https://conwaylife.com/wiki/Apgsearch
+///
+/// Soup `i` is seeded from the seed of the search and `i`, so any soup can be reproduced, the objects
+/// are counted by their apgcode.
+pub struct Census {
+    pub rule: Rule,
+    pub symmetry: Symmetry,
+    pub seed: String,
+    pub soups: u64,
+    /// Soups that did not stabilize within `MAX_GENERATIONS`.
+    pub unstable: u64,
+    pub objects: HashMap<String, u64>,
+}
+
+impl Census {
+    pub fn new(rule: Rule, symmetry: Symmetry, seed: &str) -> Result<Census, String> {
+        // Rules with B0 fill the plane, there are no objects to count
+        SparseUniverse::new(rule)?;
+        Ok(Census {
+            rule,
+            symmetry,
+            seed: seed.to_string(),
+            soups: 0,
+            unstable: 0,
+            objects: HashMap::new(),
+        })
+    }
+
+    /// Runs the next `n` soups.
+    pub fn search(&mut self, n: u64) {
+        (0..n).for_each(|_| self.search_soup());
+    }
+
+    fn search_soup(&mut self) {
+        let mut random = Random::new(soup_seed(&self.seed, self.soups));
+        let pattern = soup(&mut random, SOUP_SIZE, SOUP_DENSITY, self.symmetry);
+        self.soups += 1;
+
+        let mut universe = SparseUniverse::new(self.rule).unwrap();
+        pattern
+            .live_cells()
+            .into_iter()
+            .for_each(|(row, col)| universe.set_cell(row, col, Cell::Alive));
+        if !stabilize(&mut universe) {
+            self.unstable += 1;
+            return;
+        }
+        for code in classify(&universe.live_cells(), self.rule) {
+            *self.objects.entry(code).or_insert(0) += 1;
+        }
+    }
+
+    /// The census as JSON, objects sorted from the most common.
+    pub fn to_json(&self) -> String {
+        let mut objects: Vec<_> = self.objects.iter().collect();
+        objects.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+        let mut output = String::new();
+        writeln!(output, "{{").unwrap();
+        writeln!(output, "  \"rule\": \"{}\",", self.rule).unwrap();
+        writeln!(output, "  \"symmetry\": \"{}\",", self.symmetry).unwrap();
+        writeln!(output, "  \"seed\": \"{}\",", escape_json(&self.seed)).unwrap();
+        writeln!(output, "  \"soups\": {},", self.soups).unwrap();
+        writeln!(output, "  \"unstable\": {},", self.unstable).unwrap();
+        write!(output, "  \"census\": {{").unwrap();
+        for (i, (name, count)) in objects.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(output, "{}\n    \"{}\": {}", separator, name, count).unwrap();
+        }
+        if !objects.is_empty() {
+            write!(output, "\n  ").unwrap();
+        }
+        writeln!(output, "}}").unwrap();
+        writeln!(output, "}}").unwrap();
+        output
+    }
+}
+
+/// Runs the universe until its population is periodic, so only still lifes, oscillators and
+/// escaping spaceships are left.
+fn stabilize(universe: &mut SparseUniverse) -> bool {
+    let mut populations = vec![];
+    while universe.generation() < MAX_GENERATIONS {
+        populations.push(universe.population());
+        if universe.generation().is_multiple_of(MAX_POPULATION_PERIOD as u64) && is_periodic(&populations) {
+            return true;
+        }
+        universe.tick();
+    }
+    false
+}
+
+fn is_periodic(populations: &[u64]) -> bool {
+    (1..=MAX_POPULATION_PERIOD).any(|period| {
+        let window = (period * 8).max(100);
+        populations.len() >= window + period
+            && (populations.len() - window..populations.len()).all(|i| populations[i] == populations[i - period])
+    })
+}
+
+/// The apgcodes of the objects of a stable pattern.
+///
+/// Parts of an object that die on their own, like the halves of some pseudo still lifes, are classified
+/// together with the objects close enough to interact with them, or as `unknown` if there are none.
+fn classify(cells: &[(i64, i64)], rule: Rule) -> Vec<String> {
+    let mut objects: Vec<(Vec<(i64, i64)>, String)> = objects(cells)
+        .into_iter()
+        .map(|object| {
+            let code = apgcode(&object, rule);
+            (object, code)
+        })
+        .collect();
+    while let Some(i) = objects.iter().position(|(_, code)| code == "empty") {
+        let (part, _) = objects.swap_remove(i);
+        match objects.iter().position(|(object, _)| interact(&part, object)) {
+            Some(j) => {
+                objects[j].0.extend(part);
+                objects[j].1 = apgcode(&objects[j].0, rule);
+            }
+            None => objects.push((part, String::from("unknown"))),
+        }
+    }
+    objects.into_iter().map(|(_, code)| code).collect()
+}
+
+/// Whether cells of both objects have common neighbours.
+fn interact(a: &[(i64, i64)], b: &[(i64, i64)]) -> bool {
+    a.iter()
+        .any(|(row, col)| b.iter().any(|(r, c)| (row - r).abs() <= 2 && (col - c).abs() <= 2))
+}
+
+/// The apgcode of an object, like `xs4_33` for the block, see https://conwaylife.com/wiki/Apgcode
+///
+/// The prefix is `xs` and the population for still lifes, `xp` and the period for oscillators, or `xq`
+/// and the period for spaceships, followed by the shortest extended Wechsler format of all phases and
+/// orientations. Objects without a period are `unknown`.
+pub fn apgcode(cells: &[(i64, i64)], rule: Rule) -> String {
+    let mut universe = SparseUniverse::new(rule).unwrap();
+    cells
+        .iter()
+        .for_each(|(row, col)| universe.set_cell(*row, *col, Cell::Alive));
+    let periodicity = match find_period(&mut universe, MAX_OBJECT_GENERATIONS, MAX_OBJECT_GENERATIONS) {
+        Some(periodicity) => periodicity,
+        None => return String::from("unknown"),
+    };
+    let prefix = match periodicity.classification() {
+        Classification::Empty => return String::from("empty"),
+        Classification::StillLife => format!("xs{}", periodicity.population),
+        Classification::Oscillator => format!("xp{}", periodicity.period),
+        Classification::Spaceship => format!("xq{}", periodicity.period),
+    };
+
+    let mut best: Option<String> = None;
+    for _ in 0..periodicity.period {
+        let cells = universe.live_cells();
+        for orientation in 0..8 {
+            let oriented = cells.iter().map(|cell| orient(*cell, orientation)).collect();
+            let code = wechsler(&normalize(oriented).0);
+            if best
+                .as_ref()
+                .is_none_or(|best| (code.len(), &code) < (best.len(), best))
+            {
+                best = Some(code);
+            }
+        }
+        universe.tick();
+    }
+    format!("{}_{}", prefix, best.unwrap())
+}
+
+/// One of the 8 rotations and reflections of a cell.
+fn orient((row, col): (i64, i64), orientation: u8) -> (i64, i64) {
+    let (row, col) = if orientation & 4 != 0 { (col, row) } else { (row, col) };
+    let row = if orientation & 2 != 0 { -row } else { row };
+    let col = if orientation & 1 != 0 { -col } else { col };
+    (row, col)
+}
+
+/// The extended Wechsler format of normalized cells: strips of 5 rows separated by `z`, a digit per
+/// column of a strip, and `w`, `x` or `y` followed by a digit for runs of empty columns.
+fn wechsler(cells: &[(i64, i64)]) -> String {
+    let width = cells.iter().map(|(_, col)| col + 1).max().unwrap_or(0) as usize;
+    let height = cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+    let mut strips = vec![];
+    for strip in 0..(height + 4) / 5 {
+        let mut columns = vec![0usize; width];
+        for (row, col) in cells.iter().filter(|(row, _)| row / 5 == strip) {
+            columns[*col as usize] |= 1 << (row % 5);
+        }
+        // Only the empty columns between live cells are written
+        while columns.last() == Some(&0) {
+            columns.pop();
+        }
+
+        let mut code = String::new();
+        let mut zeros = 0;
+        for column in columns {
+            if column == 0 {
+                zeros += 1;
+                continue;
+            }
+            while zeros > 0 {
+                let run = zeros.min(39);
+                match run {
+                    1 => code.push('0'),
+                    2 => code.push('w'),
+                    3 => code.push('x'),
+                    _ => {
+                        code.push('y');
+                        code.push(WECHSLER_DIGITS[run - 4] as char);
+                    }
+                }
+                zeros -= run;
+            }
+            code.push(WECHSLER_DIGITS[column] as char);
+        }
+        strips.push(code);
+    }
+    strips.join("z")
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::Rule;
+
+    use super::classify;
+
+    #[test]
+    fn classifies_both_halves_of_a_toad() {
+        // The phase of the toad made of two diagonal lines that do not touch
+        let toad = [(0, 1), (0, 2), (1, 0), (2, 3), (3, 1), (3, 2)];
+        assert_eq!(classify(&toad, Rule::default()), ["xp2_7e"]);
+    }
+
+    #[test]
+    fn keeps_separate_objects_apart() {
+        let mut codes = classify(
+            &[(0, 0), (0, 1), (1, 0), (1, 1), (10, 10), (10, 11), (10, 12)],
+            Rule::default(),
+        );
+        codes.sort();
+        assert_eq!(codes, ["xp2_7", "xs4_33"]);
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::color::parse_color;
+use crate::game_of_life::Engine;
+
+/// Cells older than this have the oldest color.
+const MAX_AGE: u64 = 254;
+/// Generations until a dead cell fades out.
+const TRAIL_LENGTH: u64 = 32;
+/// The heat left from a change after one generation.
+const HEAT_DECAY: f32 = 0.9;
+/// The heat of a cell that changes every generation.
+const MAX_HEAT: f32 = 1.0 / (1.0 - HEAT_DECAY);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Live cells have the last color.
+    Plain,
+    /// Newborn cells have the last color and fade to the first color as they get older.
+    Age,
+    /// Cells that died recently fade out from the last color.
+    Trails,
+    /// Cells that change often are brighter, whether they are alive or not.
+    Heat,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(ColorMode::Plain),
+            "age" => Ok(ColorMode::Age),
+            "trails" => Ok(ColorMode::Trails),
+            "heat" => Ok(ColorMode::Heat),
+            _ => Err(format!(
+                "unknown color mode `{}`, expected `plain`, `age`, `trails` or `heat`",
+                s
+            )),
+        }
+    }
+}
+
+impl ColorMode {
+    pub fn next(self) -> ColorMode {
+        match self {
+            ColorMode::Plain => ColorMode::Age,
+            ColorMode::Age => ColorMode::Trails,
+            ColorMode::Trails => ColorMode::Heat,
+            ColorMode::Heat => ColorMode::Plain,
+        }
+    }
+}
+
+/// The color of dead cells and the gradient of shades `1..=255`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColorScheme {
+    pub dead: [u8; 3],
+    pub gradient: Vec<[u8; 3]>,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        "classic".parse().unwrap()
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    /// A preset, `classic`, `fire` or `ocean`, or comma separated `#rrggbb` colors, the dead color
+    /// first and then the gradient.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let colors = match s {
+            "classic" => "#ffffff,#a8c4eb,#3d67a6,#000000",
+            "fire" => "#000000,#400000,#c00000,#ff8000,#ffff80",
+            "ocean" => "#001020,#003060,#0080c0,#80e0ff",
+            colors => colors,
+        };
+        let colors = colors.split(',').map(parse_color).collect::<Result<Vec<_>, _>>()?;
+        match colors.split_first() {
+            Some((dead, gradient)) if !gradient.is_empty() => Ok(ColorScheme {
+                dead: *dead,
+                gradient: gradient.to_vec(),
+            }),
+            _ => Err(String::from(
+                "a color scheme needs a dead color and at least one more color",
+            )),
+        }
+    }
+}
+
+impl ColorScheme {
+    /// The color of every shade, the dead color first.
+    pub fn palette(&self) -> Vec<[u8; 3]> {
+        let last = self.gradient.len() - 1;
+        let mut palette = vec![self.dead];
+        palette.extend((1..=255).map(|shade| {
+            // Shade 1 is the first color and shade 255 the last color of the gradient
+            let position = (shade - 1) as f32 / 254.0 * last as f32;
+            let i = (position as usize).min(last.saturating_sub(1));
+            let (from, to) = (self.gradient[i], self.gradient[(i + 1).min(last)]);
+            let t = position - i as f32;
+            let mut color = [0; 3];
+            (0..3).for_each(|c| color[c] = (from[c] as f32 + (to[c] as f32 - from[c] as f32) * t).round() as u8);
+            color
+        }));
+        palette
+    }
+}
+
+/// The age and recent activity of cells, kept up to date from the live cells of an engine.
+#[derive(Default)]
+pub struct CellStats {
+    generation: u64,
+    /// The generation every live cell was born in.
+    born: HashMap<(i64, i64), u64>,
+    /// The generation recently dead cells died in.
+    died: HashMap<(i64, i64), u64>,
+    /// How often cells changed, decaying every generation.
+    heat: HashMap<(i64, i64), f32>,
+}
+
+impl CellStats {
+    pub fn update(&mut self, engine: &dyn Engine) {
+        let generation = engine.generation();
+        if generation < self.generation {
+            *self = CellStats::default();
+        }
+        let live_cells: HashSet<(i64, i64)> = engine.live_cells().into_iter().collect();
+
+        let decay = HEAT_DECAY.powi((generation - self.generation).min(i32::MAX as u64) as i32);
+        self.heat.values_mut().for_each(|heat| *heat *= decay);
+        let mut changed = vec![];
+        self.born.retain(|cell, _| {
+            let alive = live_cells.contains(cell);
+            if !alive {
+                changed.push(*cell);
+            }
+            alive
+        });
+        for cell in changed.iter() {
+            self.died.insert(*cell, generation);
+        }
+        for cell in live_cells {
+            if let Entry::Vacant(entry) = self.born.entry(cell) {
+                entry.insert(generation);
+                self.died.remove(&cell);
+                changed.push(cell);
+            }
+        }
+        for cell in changed {
+            *self.heat.entry(cell).or_insert(0.0) += 1.0;
+        }
+
+        self.died.retain(|_, died| generation - *died < TRAIL_LENGTH);
+        let born = &self.born;
+        self.heat.retain(|cell, heat| *heat > 0.01 || born.contains_key(cell));
+        self.generation = generation;
+    }
+
+    /// The shade `1..=255` of every cell that is not drawn as dead.
+    pub fn shades(&self, mode: ColorMode) -> Vec<((i64, i64), u8)> {
+        let generation = self.generation;
+        match mode {
+            ColorMode::Plain => self.born.keys().map(|cell| (*cell, 255)).collect(),
+            ColorMode::Age => self
+                .born
+                .iter()
+                .map(|(cell, born)| (*cell, (255 - (generation - born).min(MAX_AGE)) as u8))
+                .collect(),
+            ColorMode::Trails => self
+                .born
+                .keys()
+                .map(|cell| (*cell, 255))
+                .chain(self.died.iter().map(|(cell, died)| {
+                    let fade = (generation - died) as f32 / TRAIL_LENGTH as f32;
+                    (*cell, (1.0 + 253.0 * (1.0 - fade)) as u8)
+                }))
+                .collect(),
+            ColorMode::Heat => self
+                .heat
+                .iter()
+                .map(|(cell, heat)| (*cell, (1.0 + 254.0 * (heat / MAX_HEAT).min(1.0)) as u8))
+                .collect(),
+        }
+    }
+}
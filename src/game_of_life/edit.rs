@@ -0,0 +1,256 @@
+use crate::game_of_life::{Automaton, Engine, Random};
+
+/// A rectangle of cells, `(top, left, bottom, right)` inclusive.
+pub type Selection = (i64, i64, i64, i64);
+
+enum Drag {
+    /// Sets every cell the mouse passes over to a state, the last cell visited is kept to fill the
+    /// gaps between mouse events.
+    Paint(u8, (i64, i64)),
+    Select((i64, i64)),
+}
+
+/// Copied cells with their states, relative to the top left corner of the copied rectangle.
+struct Clipboard {
+    height: i64,
+    width: i64,
+    cells: Vec<((i64, i64), u8)>,
+}
+
+/// Editing of the cells of an engine with the mouse.
+///
+/// A click changes a cell to its next state, dragging paints the state of the first cell, and
+/// shift-dragging selects a rectangle.
+pub struct Editor {
+    drag: Option<Drag>,
+    pub selection: Option<Selection>,
+    clipboard: Option<Clipboard>,
+    /// The cell under the mouse, where the clipboard is pasted.
+    pub cursor: (i64, i64),
+    random: Random,
+}
+
+impl Editor {
+    pub fn new(seed: u64) -> Editor {
+        Editor {
+            drag: None,
+            selection: None,
+            clipboard: None,
+            cursor: (0, 0),
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn mouse_down(&mut self, engine: &mut dyn Engine, cell: (i64, i64), shift: bool) {
+        self.cursor = cell;
+        if shift {
+            self.drag = Some(Drag::Select(cell));
+            self.selection = Some(normalize(cell, cell));
+            return;
+        }
+        let paint = next_state(engine.automaton().as_ref(), engine.state(cell.0, cell.1));
+        engine.set_state(cell.0, cell.1, paint);
+        self.drag = Some(Drag::Paint(paint, cell));
+    }
+
+    pub fn mouse_move(&mut self, engine: &mut dyn Engine, cell: (i64, i64)) {
+        self.cursor = cell;
+        match self.drag {
+            Some(Drag::Paint(paint, last)) => {
+                line(last, cell).for_each(|(row, col)| engine.set_state(row, col, paint));
+                self.drag = Some(Drag::Paint(paint, cell));
+            }
+            Some(Drag::Select(start)) => self.selection = Some(normalize(start, cell)),
+            None => {}
+        }
+    }
+
+    /// Whether the mouse is changing cells.
+    pub fn painting(&self) -> bool {
+        matches!(self.drag, Some(Drag::Paint(..)))
+    }
+
+    pub fn mouse_up(&mut self) {
+        self.drag = None;
+    }
+
+    /// Kills the cells of the selection, only those around live cells need to be visited.
+    pub fn clear(&mut self, engine: &mut dyn Engine) {
+        let area = engine
+            .bounding_box()
+            .and_then(|live| self.selection.and_then(|s| intersect(s, live)));
+        self.fill_with(engine, area, |_| 0);
+    }
+
+    /// Fills the selection with the live state of the automaton.
+    pub fn fill(&mut self, engine: &mut dyn Engine) {
+        let live_state = engine.automaton().live_state();
+        self.fill_with(engine, self.selection, |_| live_state);
+    }
+
+    /// Fills the selection with cells in the live state of the automaton at the given density.
+    pub fn randomize(&mut self, engine: &mut dyn Engine, density: f64) {
+        let live_state = engine.automaton().live_state();
+        self.fill_with(engine, self.selection, |random| {
+            if random.next_f64() < density {
+                live_state
+            } else {
+                0
+            }
+        });
+    }
+
+    /// Sets the states of the cells of `area` that are inside the engine.
+    fn fill_with<F>(&mut self, engine: &mut dyn Engine, area: Option<Selection>, mut f: F)
+    where
+        F: FnMut(&mut Random) -> u8,
+    {
+        let area = match engine.bounds() {
+            Some(bounds) => area.and_then(|area| intersect(area, bounds)),
+            None => area,
+        };
+        if let Some((top, left, bottom, right)) = area {
+            for row in top..=bottom {
+                for col in left..=right {
+                    engine.set_state(row, col, f(&mut self.random));
+                }
+            }
+        }
+    }
+
+    /// Copies the live cells of the selection with their states, cropped to their bounding box.
+    pub fn copy(&mut self, engine: &dyn Engine) {
+        let area = engine
+            .bounding_box()
+            .and_then(|live| self.selection.and_then(|s| intersect(s, live)));
+        if let Some((top, left, bottom, right)) = area {
+            let cells = engine
+                .cell_states()
+                .into_iter()
+                .filter(|((row, col), _)| (top..=bottom).contains(row) && (left..=right).contains(col))
+                .map(|((row, col), state)| ((row - top, col - left), state))
+                .collect();
+            self.clipboard = Some(Clipboard {
+                height: bottom - top + 1,
+                width: right - left + 1,
+                cells,
+            });
+        }
+    }
+
+    /// Pastes the clipboard with its top left corner at the cursor, replacing the cells under it, and
+    /// selects it.
+    pub fn paste(&mut self, engine: &mut dyn Engine) {
+        if let Some(clipboard) = &self.clipboard {
+            let (top, left) = self.cursor;
+            let area = (top, left, top + clipboard.height - 1, left + clipboard.width - 1);
+            let cleared = match engine.bounds() {
+                Some(bounds) => intersect(area, bounds),
+                None => Some(area),
+            };
+            if let Some((top, left, bottom, right)) = cleared {
+                for row in top..=bottom {
+                    for col in left..=right {
+                        engine.set_state(row, col, 0);
+                    }
+                }
+            }
+            for ((row, col), state) in &clipboard.cells {
+                engine.set_state(top + row, left + col, *state);
+            }
+            self.selection = Some(area);
+        }
+    }
+}
+
+/// Dead cells become live, and live cells go through the other states back to dead, so a click
+/// toggles two-state cells.
+fn next_state(automaton: &dyn Automaton, state: u8) -> u8 {
+    let live_state = automaton.live_state();
+    let order: Vec<u8> = [0, live_state]
+        .iter()
+        .copied()
+        .chain((1..automaton.states()).filter(|state| *state != live_state))
+        .collect();
+    let i = order.iter().position(|s| *s == state).unwrap_or(0);
+    order[(i + 1) % order.len()]
+}
+
+fn normalize((row0, col0): (i64, i64), (row1, col1): (i64, i64)) -> Selection {
+    (row0.min(row1), col0.min(col1), row0.max(row1), col0.max(col1))
+}
+
+/// The cells in both rectangles, if any.
+fn intersect(a: Selection, b: Selection) -> Option<Selection> {
+    let intersection = (a.0.max(b.0), a.1.max(b.1), a.2.min(b.2), a.3.min(b.3));
+    if intersection.0 <= intersection.2 && intersection.1 <= intersection.3 {
+        Some(intersection)
+    } else {
+        None
+    }
+}
+
+/// The cells on the line from `from` to `to`, both included, by Bresenham's algorithm.
+fn line(from: (i64, i64), to: (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+    let (delta_row, delta_col) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_row, step_col) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let mut error = delta_row + delta_col;
+    let mut current = Some(from);
+    std::iter::from_fn(move || {
+        let cell = current?;
+        current = if cell == to {
+            None
+        } else {
+            let (mut row, mut col) = cell;
+            let double_error = 2 * error;
+            if double_error >= delta_col {
+                error += delta_col;
+                row += step_row;
+            }
+            if double_error <= delta_row {
+                error += delta_row;
+                col += step_col;
+            }
+            Some((row, col))
+        };
+        Some(cell)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::game_of_life::{Editor, Engine, MultiStateUniverse, Wireworld};
+
+    #[test]
+    fn copies_and_pastes_states() {
+        let mut wires = MultiStateUniverse::new(16, 16, Rc::new(Wireworld));
+        wires.set_state(2, 2, Wireworld::TAIL);
+        wires.set_state(2, 3, Wireworld::HEAD);
+        wires.set_state(2, 4, Wireworld::CONDUCTOR);
+        let mut editor = Editor::new(1);
+        editor.selection = Some((1, 1, 3, 4));
+        editor.copy(&wires);
+        editor.cursor = (10, 10);
+        editor.paste(&mut wires);
+        assert_eq!(
+            [wires.state(10, 10), wires.state(10, 11), wires.state(10, 12)],
+            [Wireworld::TAIL, Wireworld::HEAD, Wireworld::CONDUCTOR]
+        );
+        assert_eq!(editor.selection, Some((10, 10, 10, 12)));
+    }
+
+    #[test]
+    fn fills_with_the_live_state() {
+        let mut wires = MultiStateUniverse::new(4, 4, Rc::new(Wireworld));
+        let mut editor = Editor::new(1);
+        editor.selection = Some((0, 0, 1, 1));
+        editor.fill(&mut wires);
+        assert_eq!(wires.cell_states().len(), 4);
+        assert!(wires
+            .cell_states()
+            .iter()
+            .all(|(_, state)| *state == Wireworld::CONDUCTOR));
+    }
+}
@@ -0,0 +1,222 @@
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::game_of_life::{Automaton, Cell, Rule, Topology};
+
+/// A Life simulation backend that the canvas can step and view.
+///
+/// Coordinates are `(row, column)` pairs, engines with a finite grid treat everything outside of it
+/// as dead.
+pub trait Engine {
+    fn rule(&self) -> Rule;
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String>;
+
+    /// The automaton the cells follow, the Life-like rule for two-state engines.
+    fn automaton(&self) -> Rc<dyn Automaton> {
+        Rc::new(self.rule())
+    }
+
+    fn set_automaton(&mut self, automaton: Rc<dyn Automaton>) -> Result<(), String> {
+        match automaton.life_rule() {
+            Some(rule) if automaton.states() == 2 => self.set_rule(rule),
+            _ => Err(format!("`{}` is not a Life-like rule", automaton)),
+        }
+    }
+
+    /// How the edges of the grid are glued together, unbounded engines are a plane.
+    fn topology(&self) -> Topology {
+        Topology::Plane
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String>;
+
+    fn generation(&self) -> u64;
+
+    fn population(&self) -> u64;
+
+    /// Advances the pattern by `2^k` generations.
+    fn step_pow2(&mut self, k: u32);
+
+    fn cell(&self, row: i64, column: i64) -> Cell;
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell);
+
+    /// The state of a cell, two-state engines only have 0 for dead and 1 for alive.
+    fn state(&self, row: i64, column: i64) -> u8 {
+        self.cell(row, column) as u8
+    }
+
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        let cell = if state == 0 { Cell::Dead } else { Cell::Alive };
+        self.set_cell(row, column, cell);
+    }
+
+    /// The cells in any state but dead.
+    fn live_cells(&self) -> Vec<(i64, i64)>;
+
+    /// The cells in any state but dead, with their state.
+    fn cell_states(&self) -> Vec<((i64, i64), u8)> {
+        self.live_cells().into_iter().map(|cell| (cell, 1)).collect()
+    }
+
+    /// Whether any cell of the `(top, left, bottom, right)` rectangle, inclusive, is alive. This looks
+    /// at every cell, engines that know where their live cells are answer faster.
+    fn any_alive(&self, (top, left, bottom, right): (i64, i64, i64, i64)) -> bool {
+        (top..=bottom).any(|row| (left..=right).any(|column| self.state(row, column) != 0))
+    }
+
+    /// Cells drawn highlighted, like the positions of turmites.
+    fn highlights(&self) -> Vec<(i64, i64)> {
+        vec![]
+    }
+
+    /// A copy of the current generation.
+    fn snapshot(&self) -> Box<dyn Engine>;
+
+    /// An estimate of the memory used by the cells, in bytes.
+    fn memory_usage(&self) -> usize;
+
+    /// The `(top, left, bottom, right)` of the grid of engines of a finite size, inclusive, `None` for
+    /// unbounded engines. Cells outside of it are always dead.
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        None
+    }
+
+    /// The `(top, left, bottom, right)` of the live cells, inclusive.
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        let live_cells = self.live_cells();
+        let (first_row, first_col) = *live_cells.first()?;
+        Some(live_cells.iter().fold(
+            (first_row, first_col, first_row, first_col),
+            |(top, left, bottom, right), (row, col)| (top.min(*row), left.min(*col), bottom.max(*row), right.max(*col)),
+        ))
+    }
+}
+
+/// Rules with B0 turn the whole plane alive, engines without edges cannot run them.
+pub(crate) fn check_unbounded_rule(rule: Rule) -> Result<(), String> {
+    if rule.birth & 1 != 0 {
+        return Err(format!(
+            "an unbounded universe does not support rules with B0, got `{}`",
+            rule
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineKind {
+    /// The bit-packed `Universe` of a fixed size.
+    Dense,
+    HashLife,
+    /// The unbounded `SparseUniverse`.
+    Sparse,
+}
+
+impl FromStr for EngineKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dense" => Ok(EngineKind::Dense),
+            "hashlife" => Ok(EngineKind::HashLife),
+            "sparse" => Ok(EngineKind::Sparse),
+            _ => Err(format!(
+                "unknown engine `{}`, expected `dense`, `hashlife` or `sparse`",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::{Cell, Engine, HashLife, Random, Rule, SparseUniverse, Topology, Universe};
+
+    const SIZE: i64 = 768;
+
+    /// Runs `pattern` centered in a dense universe large enough for it to never reach the edges, in
+    /// HashLife and in a sparse universe, and checks that they agree on the live cells.
+    fn run_everywhere(pattern: &str, generations: u64) -> Vec<(i64, i64)> {
+        let pattern = Universe::from_rle(pattern).unwrap();
+        let mut dense = Universe::empty(SIZE as u32, SIZE as u32);
+        dense.set_topology(Topology::Plane).unwrap();
+        let mut engines: Vec<Box<dyn Engine>> = vec![
+            Box::new(dense),
+            Box::new(HashLife::new(pattern.rule()).unwrap()),
+            Box::new(SparseUniverse::new(pattern.rule()).unwrap()),
+        ];
+        let mut results = vec![];
+        for engine in engines.iter_mut() {
+            engine.set_rule(pattern.rule()).unwrap();
+            for (row, column) in pattern.live_cells() {
+                engine.set_cell(row + SIZE / 2, column + SIZE / 2, Cell::Alive);
+            }
+            (0..64)
+                .filter(|k| generations >> k & 1 == 1)
+                .for_each(|k| engine.step_pow2(k));
+            assert_eq!(engine.generation(), generations);
+            let mut cells = engine.live_cells();
+            cells.iter_mut().for_each(|(row, column)| {
+                *row -= SIZE / 2;
+                *column -= SIZE / 2;
+            });
+            cells.sort_unstable();
+            results.push(cells);
+        }
+        assert_eq!(results[0], results[1], "dense and HashLife differ");
+        assert_eq!(results[0], results[2], "dense and sparse differ");
+        results.remove(0)
+    }
+
+    #[test]
+    fn glider() {
+        let cells = run_everywhere("x = 3, y = 3\nbo$2bo$3o!", 64);
+        assert_eq!(cells, [(16, 17), (17, 18), (18, 16), (18, 17), (18, 18)]);
+    }
+
+    #[test]
+    fn r_pentomino() {
+        let cells = run_everywhere("x = 3, y = 3\nb2o$2o$bo!", 1103);
+        assert_eq!(cells.len(), 116);
+    }
+
+    #[test]
+    fn highlife_replicator() {
+        let cells = run_everywhere("x = 5, y = 5, rule = B36/S23\n2b3o$bo2bo$o3bo$o2bo$3o!", 96);
+        assert!(cells.len() > 12);
+    }
+
+    #[test]
+    fn any_alive_matches_the_cells() {
+        let mut engines: Vec<Box<dyn Engine>> = vec![
+            Box::new(Universe::empty(200, 150)),
+            Box::new(HashLife::new(Rule::default()).unwrap()),
+            Box::new(SparseUniverse::new(Rule::default()).unwrap()),
+        ];
+        let mut random = Random::new(7);
+        let cells: Vec<(i64, i64)> = (0..40)
+            .map(|_| ((random.next_f64() * 150.0) as i64, (random.next_f64() * 200.0) as i64))
+            .collect();
+        for engine in engines.iter_mut() {
+            cells
+                .iter()
+                .for_each(|(row, column)| engine.set_cell(*row, *column, Cell::Alive));
+        }
+        for _ in 0..200 {
+            let (top, left) = (
+                (random.next_f64() * 170.0) as i64 - 10,
+                (random.next_f64() * 220.0) as i64 - 10,
+            );
+            let size = 1 << (random.next_f64() * 8.0) as i64;
+            let area = (top, left, top + size - 1, left + size - 1);
+            let expected = cells
+                .iter()
+                .any(|(row, column)| (area.0..=area.2).contains(row) && (area.1..=area.3).contains(column));
+            for engine in engines.iter() {
+                assert_eq!(engine.any_alive(area), expected, "{:?}", area);
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use wasm_bindgen::JsValue;
+use web_sys::WebGlRenderingContext;
+
+use crate::game_of_life::{Canvas, Renderer};
+use crate::gl::{Attribute, AttributeType, Dimension, Program, ProgramDescription, Texture, UniformValue};
+
+/// Renders the view with WebGL: the blocks of the viewport are uploaded as a luminance texture with one
+/// texel per block, and a full-screen triangle draws the cells, the grid and the selection in one pass.
+///
+/// The shade of a texel is looked up in the palette of the canvas, uploaded as a 256x1 texture.
+pub struct GlRenderer {
+    program: Program,
+    blocks: Texture,
+    palette: Texture,
+    /// 255 for every block with a highlighted cell.
+    highlights: Texture,
+    /// The palette uploaded to `palette`.
+    colors: Vec<[u8; 3]>,
+    /// One byte per block of the view, reused between frames.
+    texels: Vec<u8>,
+    highlight_texels: Vec<u8>,
+}
+
+impl GlRenderer {
+    pub fn new() -> Result<GlRenderer, JsValue> {
+        let vertex_source = r#"
+        precision highp float;
+        attribute vec2 a_position;
+        void main() {
+          gl_Position = vec4(a_position, 0.0, 1.0);
+        }
+    "#;
+        let fragment_source = r#"
+        precision highp float;
+
+        uniform vec2 u_dimension;
+        uniform sampler2D u_blocks;
+        uniform sampler2D u_palette;
+        uniform sampler2D u_highlights;
+        uniform vec2 u_blocks_size;
+        uniform float u_block_size;
+        uniform vec2 u_offset;
+        uniform float u_scale;
+        uniform bool u_grid;
+        uniform vec4 u_selection;
+
+        const vec4 grid_color = vec4(vec3(0.8), 1.0);
+        const vec4 highlight_color = vec4(0.88, 0.19, 0.12, 1.0);
+        const vec4 selection_color = vec4(0.24, 0.4, 0.65, 0.3);
+
+        void main() {
+          // Pixels from the top left corner, like the offsets of mouse events
+          vec2 pixel = floor(vec2(gl_FragCoord.x, u_dimension.y - gl_FragCoord.y));
+          // Cells from the top left corner of the first block, `xy` is `(column, row)`
+          vec2 cell = u_offset + (pixel + 0.5) / u_scale;
+          vec2 block = floor(cell / u_block_size);
+
+          float shade = 0.0;
+          bool highlighted = false;
+          if (all(greaterThanEqual(block, vec2(0.0))) && all(lessThan(block, u_blocks_size))) {
+            shade = texture2D(u_blocks, (block + 0.5) / u_blocks_size).r;
+            highlighted = texture2D(u_highlights, (block + 0.5) / u_blocks_size).r > 0.5;
+          }
+          vec4 color = texture2D(u_palette, vec2((shade * 255.0 + 0.5) / 256.0, 0.5));
+          // The first pixel after the edge of a cell
+          if (u_grid && any(lessThan(fract(u_offset + pixel / u_scale), vec2(1.0 / u_scale)))) {
+            color = grid_color;
+          }
+          if (highlighted) {
+            color = highlight_color;
+          }
+          if (all(greaterThanEqual(pixel, u_selection.xy)) && all(lessThan(pixel, u_selection.xy + u_selection.zw))) {
+            color.rgb = mix(color.rgb, selection_color.rgb, selection_color.a);
+          }
+          gl_FragColor = color;
+        }
+    "#;
+
+        let program = Program::new(
+            "canvas",
+            ProgramDescription {
+                vertex_source,
+                fragment_source,
+                attributes: vec![Attribute {
+                    name: "a_position",
+                    attribute_type: AttributeType::Vector(Dimension::D2),
+                    vertices: vec![-1.0, -1.0, 3.0, -1.0, -1.0, 3.0],
+                }],
+                ..Default::default()
+            },
+        )?;
+        let blocks = Texture::new(&program.gl, 0)?;
+        let palette = Texture::new(&program.gl, 1)?;
+        let highlights = Texture::new(&program.gl, 2)?;
+
+        Ok(GlRenderer {
+            program,
+            blocks,
+            palette,
+            highlights,
+            colors: vec![],
+            texels: vec![],
+            highlight_texels: vec![],
+        })
+    }
+}
+
+impl Renderer for GlRenderer {
+    fn render(&mut self, canvas: &Canvas) {
+        let blocks = canvas.blocks(&mut self.texels);
+        let gl = &self.program.gl;
+        let colors = canvas.palette();
+        if self.colors != colors {
+            self.palette
+                .upload(gl, WebGlRenderingContext::RGB, (256, 1), &colors.concat())
+                .unwrap();
+            self.colors = colors;
+        }
+        self.blocks
+            .upload(
+                gl,
+                WebGlRenderingContext::LUMINANCE,
+                (blocks.columns, blocks.rows),
+                &self.texels,
+            )
+            .unwrap();
+
+        canvas.highlights(&blocks, &mut self.highlight_texels);
+        self.highlights
+            .upload(
+                gl,
+                WebGlRenderingContext::LUMINANCE,
+                (blocks.columns, blocks.rows),
+                &self.highlight_texels,
+            )
+            .unwrap();
+
+        let viewport = &canvas.viewport;
+        // Relative to the first block so that large coordinates keep their precision as `f32`
+        let offset = (
+            viewport.origin.1 - (blocks.left * blocks.size) as f64,
+            viewport.origin.0 - (blocks.top * blocks.size) as f64,
+        );
+        Program::clear_gl(gl);
+        self.program.prepare_render();
+        self.program.set_uniform(
+            "u_dimension",
+            UniformValue::Vector2([gl.drawing_buffer_width() as f32, gl.drawing_buffer_height() as f32]),
+        );
+        self.program
+            .set_uniform("u_blocks", UniformValue::Int(self.blocks.unit as i32));
+        self.program
+            .set_uniform("u_palette", UniformValue::Int(self.palette.unit as i32));
+        self.program
+            .set_uniform("u_highlights", UniformValue::Int(self.highlights.unit as i32));
+        self.program.set_uniform(
+            "u_blocks_size",
+            UniformValue::Vector2([blocks.columns as f32, blocks.rows as f32]),
+        );
+        self.program
+            .set_uniform("u_block_size", UniformValue::Float(blocks.size as f32));
+        self.program
+            .set_uniform("u_offset", UniformValue::Vector2([offset.0 as f32, offset.1 as f32]));
+        self.program
+            .set_uniform("u_scale", UniformValue::Float(viewport.scale as f32));
+        self.program.set_uniform(
+            "u_grid",
+            UniformValue::Int((canvas.grid && viewport.show_grid()) as i32),
+        );
+        let (x, y, width, height) = canvas.selection_rect().unwrap_or((0.0, 0.0, 0.0, 0.0));
+        self.program.set_uniform(
+            "u_selection",
+            UniformValue::Vector4([x as f32, y as f32, width as f32, height as f32]),
+        );
+        self.program.render();
+    }
+}
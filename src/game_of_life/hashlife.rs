@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+use std::mem;
+
+use crate::game_of_life::{check_unbounded_rule, Cell, Engine, Rule, Topology};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Deepest quadtree we build, keeps all coordinates inside of an `i64`.
+const MAX_LEVEL: u8 = 62;
+
+/// Number of nodes after which unreachable nodes and memoized results are dropped.
+const GC_THRESHOLD: usize = 1 << 22;
+
+/// A quadtree node covering `2^level x 2^level` cells, leaves are the `DEAD` and `ALIVE` nodes.
+#[derive(Clone, Copy)]
+struct Node {
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    level: u8,
+    population: u64,
+}
+
+/// Gosper's HashLife on an unbounded plane, see https://conwaylife.com/wiki/HashLife
+///
+/// Identical subtrees are shared, and the future of every node is memoized, which allows skipping
+/// `2^k` generations at once for regular patterns.
+pub struct HashLife {
+    rule: Rule,
+    generation: u64,
+    nodes: Vec<Node>,
+    ids: HashMap<[NodeId; 4], NodeId>,
+    empty: Vec<NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// Centered at `(0, 0)`, covering rows and columns in `-2^(level-1)..2^(level-1)`.
+    root: NodeId,
+}
+
+impl HashLife {
+    pub fn new(rule: Rule) -> Result<HashLife, String> {
+        check_unbounded_rule(rule)?;
+        let leaf = |population| Node {
+            nw: DEAD,
+            ne: DEAD,
+            sw: DEAD,
+            se: DEAD,
+            level: 0,
+            population,
+        };
+        let mut hashlife = HashLife {
+            rule,
+            generation: 0,
+            nodes: vec![leaf(0), leaf(1)],
+            ids: HashMap::new(),
+            empty: vec![DEAD],
+            results: HashMap::new(),
+            root: DEAD,
+        };
+        hashlife.root = hashlife.empty(3);
+        Ok(hashlife)
+    }
+
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(id) = self.ids.get(&[nw, ne, sw, se]) {
+            return *id;
+        }
+        let node = Node {
+            nw,
+            ne,
+            sw,
+            se,
+            level: self.nodes[nw as usize].level + 1,
+            population: [nw, ne, sw, se]
+                .iter()
+                .map(|id| self.nodes[*id as usize].population)
+                .sum(),
+        };
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(node);
+        self.ids.insert([nw, ne, sw, se], id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let id = self.join(e, e, e, e);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    fn level(&self) -> u8 {
+        self.node(self.root).level
+    }
+
+    /// Doubles the size of the root, keeping the pattern centered.
+    fn expand(&mut self) {
+        let root = self.node(self.root);
+        let e = self.empty(root.level - 1);
+        let nw = self.join(e, e, e, root.nw);
+        let ne = self.join(e, e, root.ne, e);
+        let sw = self.join(e, root.sw, e, e);
+        let se = self.join(root.se, e, e, e);
+        self.root = self.join(nw, ne, sw, se);
+    }
+
+    /// The node of half the size at the center of `id`.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let node = self.node(id);
+        let (nw, ne, sw, se) = (
+            self.node(node.nw),
+            self.node(node.ne),
+            self.node(node.sw),
+            self.node(node.se),
+        );
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+
+    /// Drops the live cells outside the center quarter of the root, keeping its level.
+    fn crop(&mut self) {
+        let center = self.center(self.root);
+        self.root = self.center(center);
+        self.expand();
+        self.expand();
+    }
+
+    /// Whether all live cells are inside the center quarter of the root.
+    fn is_centered(&mut self) -> bool {
+        let center = self.center(self.root);
+        let center = self.center(center);
+        self.node(center).population == self.node(self.root).population
+    }
+
+    /// The center of `id` advanced by `2^j` generations, `j` is at most `level - 2`.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.node(id);
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        if let Some(result) = self.results.get(&(id, j)) {
+            return *result;
+        }
+        if node.level == 2 {
+            let result = self.successor_level2(id);
+            self.results.insert((id, j), result);
+            return result;
+        }
+
+        let (nw, ne, sw, se) = (
+            self.node(node.nw),
+            self.node(node.ne),
+            self.node(node.sw),
+            self.node(node.se),
+        );
+        let n00 = node.nw;
+        let n01 = self.join(nw.ne, ne.nw, nw.se, ne.sw);
+        let n02 = node.ne;
+        let n10 = self.join(nw.sw, nw.se, sw.nw, sw.ne);
+        let n11 = self.join(nw.se, ne.sw, sw.ne, se.nw);
+        let n12 = self.join(ne.sw, ne.se, se.nw, se.ne);
+        let n20 = node.sw;
+        let n21 = self.join(sw.ne, se.nw, sw.se, se.sw);
+        let n22 = node.se;
+
+        // At full speed both halves advance `2^(level-3)` generations, otherwise only the second
+        // half advances and the first one just takes the centers.
+        let full_speed = j + 2 == node.level;
+        let quarter = |hashlife: &mut HashLife, id| {
+            if full_speed {
+                hashlife.successor(id, j - 1)
+            } else {
+                hashlife.center(id)
+            }
+        };
+        let c00 = quarter(self, n00);
+        let c01 = quarter(self, n01);
+        let c02 = quarter(self, n02);
+        let c10 = quarter(self, n10);
+        let c11 = quarter(self, n11);
+        let c12 = quarter(self, n12);
+        let c20 = quarter(self, n20);
+        let c21 = quarter(self, n21);
+        let c22 = quarter(self, n22);
+
+        let next_j = if full_speed { j - 1 } else { j };
+        let nw = self.join(c00, c01, c10, c11);
+        let nw = self.successor(nw, next_j);
+        let ne = self.join(c01, c02, c11, c12);
+        let ne = self.successor(ne, next_j);
+        let sw = self.join(c10, c11, c20, c21);
+        let sw = self.successor(sw, next_j);
+        let se = self.join(c11, c12, c21, c22);
+        let se = self.successor(se, next_j);
+        let result = self.join(nw, ne, sw, se);
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Advances the center 2x2 cells of a 4x4 node by one generation.
+    fn successor_level2(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        (0..4).for_each(|row| (0..4).for_each(|col| grid[row][col] = self.get(id, row as i64 - 2, col as i64 - 2)));
+
+        let mut next = [DEAD; 4];
+        for (i, (row, col)) in [(1, 1), (1, 2), (2, 1), (2, 2)].iter().enumerate() {
+            let mut live_neighbors = 0;
+            for delta_row in 0..3 {
+                for delta_col in 0..3 {
+                    if (delta_row, delta_col) != (1, 1) && grid[row + delta_row - 1][col + delta_col - 1] {
+                        live_neighbors += 1;
+                    }
+                }
+            }
+            let cell = if grid[*row][*col] { Cell::Alive } else { Cell::Dead };
+            if self.rule.next(cell, live_neighbors) == Cell::Alive {
+                next[i] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// The cell of node `id` at `(row, column)` relative to the node's center.
+    fn get(&self, id: NodeId, row: i64, column: i64) -> bool {
+        let node = self.node(id);
+        if node.level == 0 {
+            return id == ALIVE;
+        }
+        if node.population == 0 {
+            return false;
+        }
+        let offset = if node.level == 1 { 0 } else { 1 << (node.level - 2) };
+        let quadrant = match (row < 0, column < 0) {
+            (true, true) => node.nw,
+            (true, false) => node.ne,
+            (false, true) => node.sw,
+            (false, false) => node.se,
+        };
+        let row = if row < 0 { row + offset } else { row - offset };
+        let column = if column < 0 { column + offset } else { column - offset };
+        if node.level == 1 {
+            return quadrant == ALIVE;
+        }
+        self.get(quadrant, row, column)
+    }
+
+    fn set(&mut self, id: NodeId, row: i64, column: i64, alive: bool) -> NodeId {
+        let node = self.node(id);
+        if node.level == 1 {
+            let mut children = [node.nw, node.ne, node.sw, node.se];
+            children[(row >= 0) as usize * 2 + (column >= 0) as usize] = if alive { ALIVE } else { DEAD };
+            return self.join(children[0], children[1], children[2], children[3]);
+        }
+        let offset = 1 << (node.level - 2);
+        let (r, c) = (
+            if row < 0 { row + offset } else { row - offset },
+            if column < 0 { column + offset } else { column - offset },
+        );
+        match (row < 0, column < 0) {
+            (true, true) => {
+                let nw = self.set(node.nw, r, c, alive);
+                self.join(nw, node.ne, node.sw, node.se)
+            }
+            (true, false) => {
+                let ne = self.set(node.ne, r, c, alive);
+                self.join(node.nw, ne, node.sw, node.se)
+            }
+            (false, true) => {
+                let sw = self.set(node.sw, r, c, alive);
+                self.join(node.nw, node.ne, sw, node.se)
+            }
+            (false, false) => {
+                let se = self.set(node.se, r, c, alive);
+                self.join(node.nw, node.ne, node.sw, se)
+            }
+        }
+    }
+
+    fn contains(&self, row: i64, column: i64) -> bool {
+        let half = 1i64 << (self.level() - 1);
+        (-half..half).contains(&row) && (-half..half).contains(&column)
+    }
+
+    fn collect_live_cells(&self, id: NodeId, row: i64, column: i64, cells: &mut Vec<(i64, i64)>) {
+        let node = self.node(id);
+        if node.population == 0 {
+            return;
+        }
+        if node.level == 0 {
+            cells.push((row, column));
+            return;
+        }
+        let half = 1 << (node.level - 1);
+        self.collect_live_cells(node.nw, row, column, cells);
+        self.collect_live_cells(node.ne, row, column + half, cells);
+        self.collect_live_cells(node.sw, row + half, column, cells);
+        self.collect_live_cells(node.se, row + half, column + half, cells);
+    }
+
+    /// Whether node `id`, with its top left cell at `(row, column)`, has a live cell in `area`.
+    fn any_alive_in(&self, id: NodeId, row: i64, column: i64, area: (i64, i64, i64, i64)) -> bool {
+        let node = self.node(id);
+        let size = 1i64 << node.level;
+        let (top, left, bottom, right) = area;
+        if node.population == 0 || row > bottom || column > right || row + size <= top || column + size <= left {
+            return false;
+        }
+        if node.level == 0 || (row >= top && column >= left && row + size - 1 <= bottom && column + size - 1 <= right) {
+            return true;
+        }
+        let half = size / 2;
+        self.any_alive_in(node.nw, row, column, area)
+            || self.any_alive_in(node.ne, row, column + half, area)
+            || self.any_alive_in(node.sw, row + half, column, area)
+            || self.any_alive_in(node.se, row + half, column + half, area)
+    }
+
+    /// Rebuilds the node table with only the nodes reachable from the root.
+    fn collect_garbage(&mut self) {
+        *self = self.compacted();
+    }
+
+    /// A copy of the current generation with only the nodes reachable from the root.
+    fn compacted(&self) -> HashLife {
+        let mut copy = HashLife::new(self.rule).unwrap();
+        let mut remap = HashMap::new();
+        copy.root = self.copy_into(self.root, &mut copy, &mut remap);
+        copy.generation = self.generation;
+        copy
+    }
+
+    fn copy_into(&self, id: NodeId, other: &mut HashLife, remap: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if id == DEAD || id == ALIVE {
+            return id;
+        }
+        if let Some(new_id) = remap.get(&id) {
+            return *new_id;
+        }
+        let node = self.node(id);
+        let nw = self.copy_into(node.nw, other, remap);
+        let ne = self.copy_into(node.ne, other, remap);
+        let sw = self.copy_into(node.sw, other, remap);
+        let se = self.copy_into(node.se, other, remap);
+        let new_id = other.join(nw, ne, sw, se);
+        remap.insert(id, new_id);
+        new_id
+    }
+}
+
+impl Engine for HashLife {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        check_unbounded_rule(rule)?;
+        self.rule = rule;
+        self.results.clear();
+        Ok(())
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        Err(format!(
+            "HashLife runs on an unbounded plane, it does not support {:?}",
+            topology
+        ))
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.node(self.root).population
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        let k = k.min(MAX_LEVEL as u32 - 3) as u8;
+        // The pattern has to stay inside the center quarter of the root, so that after `2^k`
+        // generations, at most `2^(level-3)`, it is still inside the half returned by `successor`.
+        while self.level() < k + 3 || !self.is_centered() {
+            if self.level() >= MAX_LEVEL {
+                // Cells this far out would need a larger root than coordinates allow, they are dropped
+                self.crop();
+            } else {
+                self.expand();
+            }
+        }
+        self.root = self.successor(self.root, k);
+        self.generation += 1 << k;
+
+        if self.nodes.len() > GC_THRESHOLD {
+            self.collect_garbage();
+        }
+    }
+
+    fn cell(&self, row: i64, column: i64) -> Cell {
+        if self.contains(row, column) && self.get(self.root, row, column) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell) {
+        while !self.contains(row, column) {
+            if self.level() >= MAX_LEVEL {
+                return;
+            }
+            self.expand();
+        }
+        self.root = self.set(self.root, row, column, cell == Cell::Alive);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = vec![];
+        let half = 1 << (self.level() - 1);
+        self.collect_live_cells(self.root, -half, -half, &mut cells);
+        cells
+    }
+
+    /// From the populations of the nodes, without going into those entirely inside or outside.
+    fn any_alive(&self, area: (i64, i64, i64, i64)) -> bool {
+        let half = 1 << (self.level() - 1);
+        self.any_alive_in(self.root, -half, -half, area)
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.compacted())
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.nodes.len() * (mem::size_of::<Node>() + mem::size_of::<([NodeId; 4], NodeId)>())
+            + self.results.len() * mem::size_of::<((NodeId, u8), NodeId)>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::{Cell, Engine, HashLife, Rule};
+
+    #[test]
+    fn drops_cells_beyond_the_largest_root() {
+        let mut hashlife = HashLife::new(Rule::default()).unwrap();
+        [(0, -1), (0, 0), (0, 1)]
+            .iter()
+            .for_each(|(row, column)| hashlife.set_cell(*row, *column, Cell::Alive));
+        let edge = (1 << 61) - 4;
+        [(edge, 0), (edge, 1), (edge + 1, 0), (edge + 1, 1)]
+            .iter()
+            .for_each(|(row, column)| hashlife.set_cell(*row, *column, Cell::Alive));
+        hashlife.step_pow2(4);
+        assert_eq!(hashlife.generation(), 16);
+        assert_eq!(hashlife.live_cells().len(), 3);
+        assert!(!hashlife.any_alive((edge - 8, -8, edge + 3, 8)));
+    }
+}
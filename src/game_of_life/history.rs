@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+
+use crate::game_of_life::Engine;
+
+/// Generations between two keyframes.
+pub const KEYFRAME_INTERVAL: u64 = 32;
+pub const DEFAULT_MEMORY_LIMIT: usize = 64 << 20;
+
+/// The past generations of an engine, kept as a keyframe every `interval` generations.
+///
+/// Any other generation is replayed from the closest keyframe before it, and the oldest keyframes are
+/// dropped once they use more than `memory_limit` bytes, the latest keyframe is always kept.
+pub struct History {
+    keyframes: VecDeque<Box<dyn Engine>>,
+    interval: u64,
+    memory_limit: usize,
+    /// The latest generation recorded, which can be after the current one after stepping back.
+    latest: u64,
+}
+
+impl History {
+    pub fn new(interval: u64, memory_limit: usize) -> History {
+        History {
+            keyframes: VecDeque::new(),
+            interval,
+            memory_limit,
+            latest: 0,
+        }
+    }
+
+    /// Records the generation the engine advanced to.
+    pub fn record(&mut self, engine: &dyn Engine) {
+        let generation = engine.generation();
+        self.latest = self.latest.max(generation);
+        match self.keyframes.back() {
+            Some(keyframe) if generation < keyframe.generation() + self.interval => {}
+            _ => self.push(engine.snapshot()),
+        }
+    }
+
+    /// Forgets the generations after the current one of the engine and keeps a keyframe of it, the
+    /// future has to be recomputed after cells are edited or the rule changes.
+    pub fn reset(&mut self, engine: &dyn Engine) {
+        let generation = engine.generation();
+        while self
+            .keyframes
+            .back()
+            .is_some_and(|keyframe| keyframe.generation() >= generation)
+        {
+            self.keyframes.pop_back();
+        }
+        self.latest = generation;
+        self.push(engine.snapshot());
+    }
+
+    fn push(&mut self, keyframe: Box<dyn Engine>) {
+        self.keyframes.push_back(keyframe);
+        self.shrink();
+    }
+
+    fn shrink(&mut self) {
+        while self.keyframes.len() > 1 && self.memory_usage() > self.memory_limit {
+            self.keyframes.pop_front();
+        }
+    }
+
+    pub fn set_memory_limit(&mut self, bytes: usize) {
+        self.memory_limit = bytes;
+        self.shrink();
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.keyframes.iter().map(|keyframe| keyframe.memory_usage()).sum()
+    }
+
+    /// The first and the latest generation recorded.
+    pub fn range(&self) -> Option<(u64, u64)> {
+        self.keyframes
+            .front()
+            .map(|keyframe| (keyframe.generation(), self.latest))
+    }
+
+    /// The engine at the given generation, replayed from the closest keyframe before it, or `None`
+    /// if it is before the first keyframe.
+    pub fn seek(&self, generation: u64) -> Option<Box<dyn Engine>> {
+        let keyframe = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|keyframe| keyframe.generation() <= generation)?;
+        let mut engine = keyframe.snapshot();
+        advance(engine.as_mut(), generation - keyframe.generation());
+        Some(engine)
+    }
+}
+
+/// Advances the engine by any number of generations, in steps of powers of two.
+pub fn advance(engine: &mut dyn Engine, generations: u64) {
+    (0..64)
+        .rev()
+        .filter(|k| generations >> k & 1 == 1)
+        .for_each(|k| engine.step_pow2(k));
+}
@@ -0,0 +1,32 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game_of_life::Census;
+
+/// A `Census` of random soups, exported to JavaScript so that a search can run in batches.
+#[wasm_bindgen(js_name = Census)]
+pub struct JsCensus {
+    census: Census,
+}
+
+#[wasm_bindgen(js_class = Census)]
+impl JsCensus {
+    /// Starts a search of soups with the `C1`, `D2`, `D4` or `D8` symmetry.
+    #[wasm_bindgen(constructor)]
+    pub fn new(rule: &str, symmetry: &str, seed: &str) -> Result<JsCensus, JsValue> {
+        Ok(JsCensus {
+            census: Census::new(rule.parse()?, symmetry.parse()?, seed)?,
+        })
+    }
+
+    pub fn search(&mut self, soups: u32) {
+        self.census.search(soups.into());
+    }
+
+    pub fn soups(&self) -> f64 {
+        self.census.soups as f64
+    }
+
+    pub fn to_json(&self) -> String {
+        self.census.to_json()
+    }
+}
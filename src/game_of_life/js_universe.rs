@@ -0,0 +1,104 @@
+use wasm_bindgen::prelude::*;
+
+use crate::game_of_life::{Cell, Universe};
+
+/// The bit-packed `Universe`, exported to JavaScript.
+///
+/// The cells can be read without copying from the wasm memory at `cells_ptr()`: every row is
+/// `words_per_row()` little-endian 64 bit words, so in a `Uint8Array` the cell at `(row, column)`
+/// is bit `column % 8` of byte `row * words_per_row() * 8 + column / 8`.
+#[wasm_bindgen(js_name = Universe)]
+pub struct JsUniverse {
+    universe: Universe,
+}
+
+#[wasm_bindgen(js_class = Universe)]
+impl JsUniverse {
+    /// Creates an empty toroidal universe following Conway's rule.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> JsUniverse {
+        JsUniverse {
+            universe: Universe::empty(width, height),
+        }
+    }
+
+    /// Parses an RLE, plaintext or Life 1.06 pattern into a universe of its size.
+    pub fn from_pattern(pattern: &str) -> Result<JsUniverse, JsValue> {
+        Ok(JsUniverse {
+            universe: Universe::from_pattern(pattern)?,
+        })
+    }
+
+    pub fn tick(&mut self, n: u32) {
+        (0..n).for_each(|_| self.universe.tick());
+    }
+
+    pub fn toggle(&mut self, row: u32, column: u32) -> Result<(), JsValue> {
+        self.check(row, column)?;
+        let cell = match self.universe.get(row, column) {
+            Cell::Dead => Cell::Alive,
+            Cell::Alive => Cell::Dead,
+        };
+        self.universe.set(row, column, cell);
+        Ok(())
+    }
+
+    /// Makes the cells alive, given as flattened `[row, column, row, column, ...]` pairs. Returns an error
+    /// and changes no cells if any of them is outside of the universe.
+    pub fn set_cells(&mut self, cells: &[u32]) -> Result<(), JsValue> {
+        for cell in cells.chunks_exact(2) {
+            self.check(cell[0], cell[1])?;
+        }
+        cells
+            .chunks_exact(2)
+            .for_each(|cell| self.universe.set(cell[0], cell[1], Cell::Alive));
+        Ok(())
+    }
+
+    /// Cells outside of the universe are dead.
+    pub fn is_alive(&self, row: u32, column: u32) -> bool {
+        row < self.universe.height && column < self.universe.width && self.universe.get(row, column) == Cell::Alive
+    }
+
+    pub fn width(&self) -> u32 {
+        self.universe.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.universe.height
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.universe.generation as f64
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        self.universe.rule = rule.parse()?;
+        Ok(())
+    }
+
+    pub fn words_per_row(&self) -> usize {
+        self.universe.words_per_row()
+    }
+
+    /// The current generation in wasm memory, the pointer changes with every tick.
+    pub fn cells_ptr(&self) -> *const u64 {
+        self.universe.words().as_ptr()
+    }
+
+    pub fn to_rle(&self) -> String {
+        self.universe.to_rle()
+    }
+}
+
+impl JsUniverse {
+    fn check(&self, row: u32, column: u32) -> Result<(), String> {
+        if row >= self.universe.height || column >= self.universe.width {
+            return Err(format!(
+                "cell ({}, {}) is outside of the {}x{} universe",
+                row, column, self.universe.width, self.universe.height
+            ));
+        }
+        Ok(())
+    }
+}
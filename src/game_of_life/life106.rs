@@ -0,0 +1,102 @@
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use crate::game_of_life::{Cell, Universe, MAX_PATTERN_CELLS};
+
+const HEADER: &str = "#Life 1.06";
+
+impl Universe {
+    /// Parses a pattern in the Life 1.06 format, see
+    /// https://conwaylife.com/wiki/Life_1.06
+    ///
+    /// The coordinates are relative, the resulting universe is the bounding box of the live cells.
+    pub fn from_life106(source: &str) -> Result<Universe, String> {
+        let mut lines = source.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        match lines.next() {
+            Some((_, line)) if line.trim() == HEADER => {}
+            _ => return Err(format!("missing `{}` header", HEADER)),
+        }
+
+        let mut coordinates = vec![];
+        for (line_number, line) in lines {
+            let parse = |value: Option<&str>| {
+                value
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .ok_or_else(|| format!("line {}: expected `x y` coordinates, found `{}`", line_number + 1, line))
+            };
+            let mut values = line.split_whitespace();
+            let x = parse(values.next())?;
+            let y = parse(values.next())?;
+            if values.next().is_some() {
+                return Err(format!(
+                    "line {}: expected `x y` coordinates, found `{}`",
+                    line_number + 1,
+                    line
+                ));
+            }
+            coordinates.push((x, y));
+        }
+
+        let min_x = coordinates.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let max_x = coordinates.iter().map(|(x, _)| *x).max().unwrap_or(-1);
+        let min_y = coordinates.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let max_y = coordinates.iter().map(|(_, y)| *y).max().unwrap_or(-1);
+        let dimension = |min: i64, max: i64| {
+            max.checked_sub(min)
+                .and_then(|size| u32::try_from(size + 1).ok())
+                .ok_or_else(|| String::from("pattern is too large"))
+        };
+
+        let (width, height) = (dimension(min_x, max_x)?, dimension(min_y, max_y)?);
+        // The cells are sparse, but the universe holds their whole bounding box
+        if width as u64 * height as u64 > MAX_PATTERN_CELLS {
+            return Err(format!(
+                "pattern is too large, {}x{} is more than {} cells",
+                width, height, MAX_PATTERN_CELLS
+            ));
+        }
+        let mut universe = Universe::empty(width, height);
+        coordinates.into_iter().for_each(|(x, y)| {
+            universe.set((y - min_y) as u32, (x - min_x) as u32, Cell::Alive);
+        });
+        Ok(universe)
+    }
+
+    pub fn to_life106(&self) -> String {
+        let mut output = format!("{}\n", HEADER);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.get(row, col) == Cell::Alive {
+                    writeln!(output, "{} {}", col, row).unwrap();
+                }
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::Universe;
+
+    #[test]
+    fn round_trips() {
+        let source = "#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n";
+        assert_eq!(Universe::from_life106(source).unwrap().to_life106(), source);
+    }
+
+    #[test]
+    fn moves_the_bounding_box_to_the_origin() {
+        let universe = Universe::from_life106("#Life 1.06\n-3 -5\n-2 -4\n").unwrap();
+        assert_eq!((universe.width, universe.height), (2, 2));
+        assert_eq!(universe.to_life106(), "#Life 1.06\n0 0\n1 1\n");
+    }
+
+    #[test]
+    fn rejects_huge_spans() {
+        let error = Universe::from_life106("#Life 1.06\n0 0\n1000000 1000000\n")
+            .err()
+            .unwrap();
+        assert!(error.starts_with("pattern is too large"), "{}", error);
+    }
+}
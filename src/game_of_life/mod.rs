@@ -0,0 +1,455 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+use crate::dom;
+
+mod analysis;
+mod automaton;
+mod canvas;
+mod census;
+mod coloring;
+mod edit;
+mod engine;
+mod gl_renderer;
+mod hashlife;
+mod history;
+mod js_census;
+mod js_universe;
+mod life106;
+mod multistate;
+mod plaintext;
+mod random;
+mod renderer;
+mod rle;
+mod rule;
+mod soup;
+mod sparse;
+mod topology;
+mod universe;
+mod viewport;
+
+pub use analysis::*;
+pub use automaton::*;
+pub use canvas::*;
+pub use census::*;
+pub use coloring::*;
+pub use edit::*;
+pub use engine::*;
+pub use gl_renderer::*;
+pub use hashlife::*;
+pub use history::*;
+pub use js_census::*;
+pub use js_universe::*;
+pub use multistate::*;
+pub use random::*;
+pub use renderer::*;
+pub use rule::*;
+pub use soup::*;
+pub use sparse::*;
+pub use topology::*;
+pub use universe::*;
+pub use viewport::*;
+
+#[wasm_bindgen]
+pub struct GameOfLife {
+    pub(crate) canvas: Rc<RefCell<Canvas>>,
+    engine: EngineKind,
+}
+
+impl GameOfLife {
+    fn create_engine(&self, pattern: Universe) -> Result<Box<dyn Engine>, String> {
+        let mut engine: Box<dyn Engine> = match self.engine {
+            EngineKind::Dense => return GameOfLife::create_universe(pattern),
+            EngineKind::HashLife => Box::new(HashLife::new(pattern.rule)?),
+            EngineKind::Sparse => Box::new(SparseUniverse::new(pattern.rule)?),
+        };
+        let (center_row, center_col) = self.canvas.borrow().viewport.center();
+        let row = center_row as i64 - pattern.height as i64 / 2;
+        let col = center_col as i64 - pattern.width as i64 / 2;
+        pattern.live_cells().into_iter().for_each(|(r, c)| {
+            engine.set_cell(row + r, col + c, Cell::Alive);
+        });
+        Ok(engine)
+    }
+
+    fn load_pattern(&self, pattern: Universe) -> Result<(), String> {
+        let engine = self.create_engine(pattern)?;
+        let mut canvas = self.canvas.borrow_mut();
+        canvas.engine = engine;
+        canvas.history = History::new(KEYFRAME_INTERVAL, DEFAULT_MEMORY_LIMIT);
+        canvas.edited = true;
+        canvas.fit();
+        Ok(())
+    }
+
+    /// Runs the cells with the automaton, moving them to a `MultiStateUniverse` if the engine only runs
+    /// Life-like rules.
+    fn set_automaton(&self, automaton: Rc<dyn Automaton>) -> Result<(), String> {
+        let mut canvas = self.canvas.borrow_mut();
+        if let Err(error) = canvas.engine.set_automaton(automaton.clone()) {
+            // Other engines, like turmites, have their own kind of rule
+            if canvas.engine.automaton().life_rule().is_none() {
+                return Err(error);
+            }
+            canvas.engine = Box::new(MultiStateUniverse::from_engine(canvas.engine.as_ref(), automaton));
+            canvas.fit();
+        }
+        canvas.edited = true;
+        Ok(())
+    }
+
+    fn create_universe(pattern: Universe) -> Result<Box<dyn Engine>, String> {
+        let size = pattern.width.max(pattern.height).max(64);
+        let mut universe = Universe::empty(size, size);
+        universe.paste(
+            &pattern,
+            universe.height.saturating_sub(pattern.height) / 2,
+            universe.width.saturating_sub(pattern.width) / 2,
+        )?;
+        universe.rule = pattern.rule;
+        universe.set_topology(pattern.topology())?;
+        universe.name = pattern.name;
+        universe.comments = pattern.comments;
+        Ok(Box::new(universe))
+    }
+}
+
+#[wasm_bindgen]
+impl GameOfLife {
+    /// Replaces the universe with the given RLE, plaintext or Life 1.06 pattern, centered.
+    pub fn load(&self, pattern: &str) -> Result<(), JsValue> {
+        self.load_pattern(Universe::from_pattern(pattern)?)?;
+        Ok(())
+    }
+
+    /// Replaces the universe with a random soup of the given density and `C1`, `D2`, `D4` or `D8`
+    /// symmetry, `width x height` cells in the center, 64x64 by default. The same seed always gives the
+    /// same soup.
+    pub fn soup(
+        &self,
+        seed: &str,
+        density: f64,
+        symmetry: &str,
+        width: Option<u32>,
+        height: Option<u32>,
+    ) -> Result<(), JsValue> {
+        check_density(density)?;
+        let mut pattern = Universe::random(
+            width.unwrap_or(64),
+            height.unwrap_or(64),
+            seed,
+            density,
+            symmetry.parse()?,
+        )?;
+        let automaton = self.canvas.borrow().engine.automaton();
+        pattern.rule = automaton.life_rule().unwrap_or_default();
+        pattern.name = Some(format!("{} soup {}", symmetry, seed));
+        self.load_pattern(pattern)?;
+        if automaton.states() > 2 {
+            self.set_automaton(automaton)?;
+        }
+        Ok(())
+    }
+
+    /// Sets a Life-like rule in `B3/S23` or `23/3` notation, a Generations rule like `/2/3` for Brian's
+    /// Brain, or `Wireworld`.
+    pub fn set_rule(&self, rule: &str) -> Result<(), JsValue> {
+        self.set_automaton(parse_automaton(rule)?)?;
+        Ok(())
+    }
+
+    /// Sets how the edges of the universe are joined: `plane`, `torus`, `klein`, `cross` or `sphere`.
+    pub fn set_topology(&self, topology: &str) -> Result<(), JsValue> {
+        let mut canvas = self.canvas.borrow_mut();
+        canvas.engine.set_topology(topology.parse()?)?;
+        canvas.edited = true;
+        Ok(())
+    }
+
+    /// Makes every step advance `2^k` generations.
+    pub fn set_step(&self, k: u32) {
+        self.canvas.borrow_mut().step_exponent = k;
+    }
+
+    pub fn step_back(&self) {
+        self.canvas.borrow_mut().step_back();
+    }
+
+    /// Goes back or forward to the given generation.
+    pub fn seek(&self, generation: f64) {
+        self.canvas.borrow_mut().seek(generation as u64);
+    }
+
+    /// Limits the memory used by the history, the oldest generations are forgotten first.
+    pub fn set_history_limit(&self, megabytes: f64) {
+        self.canvas
+            .borrow_mut()
+            .history
+            .set_memory_limit((megabytes * (1 << 20) as f64) as usize);
+    }
+
+    /// Runs a copy of the pattern until it repeats and describes it, like `period 2 oscillator`.
+    pub fn analyze(&self, max_generations: f64) -> String {
+        let mut engine = self.canvas.borrow().engine.snapshot();
+        let max_generations = max_generations as u64;
+        match find_period(engine.as_mut(), max_generations, max_generations) {
+            Some(periodicity) => format!("{} from generation {}", periodicity, periodicity.generation),
+            None => format!("no period within {} generations", max_generations),
+        }
+    }
+
+    /// Describes every group of touching cells as if it was alone, one per line.
+    pub fn analyze_objects(&self, max_generations: f64) -> Result<String, JsValue> {
+        let canvas = self.canvas.borrow();
+        if canvas.engine.automaton().states() > 2 {
+            return Err(JsValue::from("objects can only be analyzed with Life-like rules"));
+        }
+        let max_generations = max_generations as u64;
+        let mut lines = vec![];
+        for object in objects(&canvas.engine.live_cells()) {
+            let (top, left) = normalize(object.clone()).1;
+            let description = match analyze_object(&object, canvas.engine.rule(), max_generations, max_generations)? {
+                Some(periodicity) => periodicity.to_string(),
+                None => format!("no period within {} generations", max_generations),
+            };
+            lines.push(format!(
+                "{} cells at ({}, {}): {}",
+                object.len(),
+                top,
+                left,
+                description
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Zooms and pans to show the whole pattern.
+    pub fn fit(&self) {
+        self.canvas.borrow_mut().fit();
+    }
+
+    /// Shows or hides the lines between cells.
+    pub fn set_grid(&self, grid: bool) {
+        self.canvas.borrow_mut().grid = grid;
+    }
+
+    /// Whether to highlight cells like the positions of turmites.
+    pub fn set_highlight(&self, highlight: bool) {
+        self.canvas.borrow_mut().highlight = highlight;
+    }
+
+    /// Advances by the given number of generations at once.
+    pub fn fast_forward(&self, generations: f64) {
+        self.canvas.borrow_mut().fast_forward(generations as u64);
+    }
+
+    /// Colors cells by `plain`, `age`, `trails` or `heat`.
+    pub fn set_color_mode(&self, mode: &str) -> Result<(), JsValue> {
+        self.canvas.borrow_mut().color_mode = mode.parse()?;
+        Ok(())
+    }
+
+    /// Sets the colors to `classic`, `fire`, `ocean` or comma separated `#rrggbb` colors, the dead color
+    /// first.
+    pub fn set_color_scheme(&self, scheme: &str) -> Result<(), JsValue> {
+        self.canvas.borrow_mut().color_scheme = scheme.parse()?;
+        Ok(())
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.canvas.borrow().engine.generation() as f64
+    }
+
+    pub fn population(&self) -> f64 {
+        self.canvas.borrow().engine.population() as f64
+    }
+
+    /// Saves the live cells of the current generation as RLE.
+    pub fn to_rle(&self) -> String {
+        Universe::from_engine(self.canvas.borrow().engine.as_ref()).to_rle()
+    }
+
+    pub fn to_cells(&self) -> String {
+        Universe::from_engine(self.canvas.borrow().engine.as_ref()).to_cells()
+    }
+
+    pub fn to_life106(&self) -> String {
+        Universe::from_engine(self.canvas.borrow().engine.as_ref()).to_life106()
+    }
+}
+
+/// Runs the demo on the `dense`, `hashlife` or `sparse` engine, the dense universe by default, drawn by the
+/// `2d` canvas or `webgl` renderer, the 2d canvas by default.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn game_of_life(engine: Option<String>, renderer: Option<String>) -> Result<GameOfLife, JsValue> {
+    let kind = engine.as_deref().unwrap_or("dense").parse()?;
+    let game = GameOfLife::start(Box::new(Universe::new(64, 64)), kind, renderer)?;
+    if kind != EngineKind::Dense {
+        game.load_pattern(Universe::new(64, 64))?;
+    }
+    Ok(game)
+}
+
+impl GameOfLife {
+    /// Shows the engine on the canvas of the page and runs it, with `kind` engines for loaded patterns.
+    pub(crate) fn start(
+        engine: Box<dyn Engine>,
+        kind: EngineKind,
+        renderer: Option<String>,
+    ) -> Result<GameOfLife, JsValue> {
+        let renderer: Box<dyn Renderer> = match renderer.as_deref().unwrap_or("2d").parse()? {
+            RendererKind::Canvas2d => Box::new(Canvas2dRenderer::new()),
+            RendererKind::WebGl => Box::new(GlRenderer::new()?),
+        };
+        let game = GameOfLife {
+            canvas: Rc::new(RefCell::new(Canvas::new(engine))),
+            engine: kind,
+        };
+        game.canvas.borrow_mut().fit();
+        game.listen(renderer);
+        Ok(game)
+    }
+
+    /// Handles the keyboard, the mouse and the timeline, steps the engine and renders every frame.
+    fn listen(&self, renderer: Box<dyn Renderer>) {
+        {
+            let canvas = self.canvas.clone();
+            dom::add_keyboard_event_listener("keydown", move |e| {
+                let mut canvas = canvas.borrow_mut();
+                let viewport = &mut canvas.viewport;
+                let (width, height) = (viewport.width as f64, viewport.height as f64);
+                match e.key().as_str() {
+                    "ArrowUp" => viewport.pan(0.0, height / 4.0),
+                    "ArrowDown" => viewport.pan(0.0, -height / 4.0),
+                    "ArrowLeft" => viewport.pan(width / 4.0, 0.0),
+                    "ArrowRight" => viewport.pan(-width / 4.0, 0.0),
+                    "+" | "=" => viewport.zoom(width / 2.0, height / 2.0, 2.0),
+                    "-" => viewport.zoom(width / 2.0, height / 2.0, 0.5),
+                    "Home" => canvas.fit(),
+                    "," => canvas.step_back(),
+                    "." => canvas.step_forward(),
+                    " " => canvas.paused = !canvas.paused,
+                    "g" => canvas.grid = !canvas.grid,
+                    "h" => canvas.highlight = !canvas.highlight,
+                    "m" => canvas.color_mode = canvas.color_mode.next(),
+                    _ => return,
+                }
+                e.prevent_default();
+            });
+        }
+
+        let html_canvas = dom::canvas("canvas");
+        {
+            let canvas = self.canvas.clone();
+            dom::add_mouse_event_listener(&html_canvas, "mousedown", move |e| {
+                let canvas = &mut *canvas.borrow_mut();
+                // The middle and right buttons drag the view
+                if e.button() != 0 {
+                    canvas.pan = Some((e.offset_x(), e.offset_y()));
+                    return;
+                }
+                let cell = canvas.cell_at(e.offset_x(), e.offset_y());
+                canvas.editor.mouse_down(canvas.engine.as_mut(), cell, e.shift_key());
+                canvas.edited |= canvas.editor.painting();
+            });
+        }
+        {
+            let canvas = self.canvas.clone();
+            dom::add_mouse_event_listener(&html_canvas, "mousemove", move |e| {
+                let canvas = &mut *canvas.borrow_mut();
+                if let Some((x, y)) = canvas.pan {
+                    canvas
+                        .viewport
+                        .pan((e.offset_x() - x).into(), (e.offset_y() - y).into());
+                    canvas.pan = Some((e.offset_x(), e.offset_y()));
+                    return;
+                }
+                let cell = canvas.cell_at(e.offset_x(), e.offset_y());
+                canvas.editor.mouse_move(canvas.engine.as_mut(), cell);
+                canvas.edited |= canvas.editor.painting();
+            });
+        }
+        {
+            let canvas = self.canvas.clone();
+            dom::add_mouse_event_listener(&html_canvas, "mouseup", move |_e| {
+                let mut canvas = canvas.borrow_mut();
+                canvas.pan = None;
+                canvas.editor.mouse_up();
+            });
+        }
+        dom::add_mouse_event_listener(&html_canvas, "contextmenu", |e| e.prevent_default());
+        {
+            let canvas = self.canvas.clone();
+            dom::add_wheel_event_listener(&html_canvas, move |e| {
+                let pixels = match e.delta_mode() {
+                    web_sys::WheelEvent::DOM_DELTA_LINE => e.delta_y() * 16.0,
+                    web_sys::WheelEvent::DOM_DELTA_PAGE => e.delta_y() * 400.0,
+                    _ => e.delta_y(),
+                };
+                let (x, y) = (e.offset_x().into(), e.offset_y().into());
+                canvas.borrow_mut().viewport.zoom(x, y, (-pixels / 200.0).exp2());
+                e.prevent_default();
+            });
+        }
+        {
+            let canvas = self.canvas.clone();
+            dom::add_keyboard_event_listener("keydown", move |e| {
+                let canvas = &mut *canvas.borrow_mut();
+                let (editor, engine) = (&mut canvas.editor, canvas.engine.as_mut());
+                let key = e.key();
+                match (key.as_str(), e.ctrl_key() || e.meta_key()) {
+                    ("Delete", _) | ("Backspace", _) => editor.clear(engine),
+                    ("f", false) => editor.fill(engine),
+                    ("r", false) => editor.randomize(engine, 0.5),
+                    ("c", true) => editor.copy(engine),
+                    ("x", true) => {
+                        editor.copy(engine);
+                        editor.clear(engine);
+                    }
+                    ("v", true) => editor.paste(engine),
+                    ("Escape", _) => editor.selection = None,
+                    _ => return,
+                }
+                canvas.edited |= key != "c" && key != "Escape";
+                e.prevent_default();
+            });
+        }
+
+        {
+            let canvas = self.canvas.clone();
+            dom::set_interval(50, move || {
+                canvas.borrow_mut().step();
+            });
+        }
+
+        let timeline = dom::input("timeline");
+        {
+            let canvas = self.canvas.clone();
+            let input = timeline.clone();
+            dom::add_event_listener(&timeline, "input", move |_e| {
+                let mut canvas = canvas.borrow_mut();
+                canvas.paused = true;
+                canvas.seek(input.value_as_number() as u64);
+            });
+        }
+
+        {
+            let canvas = self.canvas.clone();
+            let renderer = RefCell::new(renderer);
+            dom::request_animation_frame(move |_t, _dt| {
+                let mut canvas = canvas.borrow_mut();
+                canvas.viewport.width = html_canvas.width();
+                canvas.viewport.height = html_canvas.height();
+                canvas.update_stats();
+                renderer.borrow_mut().render(&canvas);
+                if let Some((first, latest)) = canvas.history.range() {
+                    timeline.set_min(&first.to_string());
+                    timeline.set_max(&latest.to_string());
+                    timeline.set_value_as_number(canvas.engine.generation() as f64);
+                }
+            });
+        }
+    }
+}
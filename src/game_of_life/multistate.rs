@@ -0,0 +1,231 @@
+use std::rc::Rc;
+
+use crate::game_of_life::{Automaton, Cell, Engine, Rule, Topology};
+
+/// A finite universe of cells with any number of states, one byte per cell, run by any `Automaton`.
+///
+/// This is slower than the bit-packed `Universe`, which only runs Life-like rules.
+#[derive(Clone)]
+pub struct MultiStateUniverse {
+    pub width: u32,
+    pub height: u32,
+    cells: Vec<u8>,
+    /// The next generation, swapped with `cells` after every tick.
+    next: Vec<u8>,
+    topology: Topology,
+    automaton: Rc<dyn Automaton>,
+    pub generation: u64,
+}
+
+impl MultiStateUniverse {
+    pub fn new(width: u32, height: u32, automaton: Rc<dyn Automaton>) -> MultiStateUniverse {
+        let size = width as usize * height as usize;
+        MultiStateUniverse {
+            width,
+            height,
+            cells: vec![0; size],
+            next: vec![0; size],
+            topology: Topology::default(),
+            automaton,
+            generation: 0,
+        }
+    }
+
+    /// Copies the cells of `engine` into a universe of the same size and topology. The cells of unbounded
+    /// engines go into a square universe around them, at least 64x64, centered like a loaded pattern.
+    pub fn from_engine(engine: &dyn Engine, automaton: Rc<dyn Automaton>) -> MultiStateUniverse {
+        let (mut universe, row, col) = match engine.bounds() {
+            Some((top, left, bottom, right)) => {
+                let (width, height) = ((right - left + 1) as u32, (bottom - top + 1) as u32);
+                let mut universe = MultiStateUniverse::new(width, height, automaton);
+                universe.topology = engine.topology();
+                (universe, -top, -left)
+            }
+            None => {
+                let (top, left, bottom, right) = engine.bounding_box().unwrap_or((0, 0, -1, -1));
+                let (width, height) = (right - left + 1, bottom - top + 1);
+                let size = width.max(height).max(64);
+                let universe = MultiStateUniverse::new(size as u32, size as u32, automaton);
+                (universe, (size - height) / 2 - top, (size - width) / 2 - left)
+            }
+        };
+        universe.generation = engine.generation();
+        for ((r, c), state) in engine.cell_states() {
+            universe.set_state(row + r, col + c, state);
+        }
+        universe
+    }
+
+    pub fn get(&self, row: u32, column: u32) -> u8 {
+        self.cells[row as usize * self.width as usize + column as usize]
+    }
+
+    pub fn set(&mut self, row: u32, column: u32, state: u8) {
+        let states = self.automaton.states();
+        self.cells[row as usize * self.width as usize + column as usize] = if state < states { state } else { 0 };
+    }
+
+    /// Advances one generation, the cells inside the edges without going through the topology.
+    pub fn tick(&mut self) {
+        let mut next_cells = std::mem::take(&mut self.next);
+        let (width, height) = (self.width as i64, self.height as i64);
+        let cells = &self.cells;
+        let state = |row: i64, column: i64| cells[(row * width + column) as usize];
+        for row in 0..height {
+            for column in 0..width {
+                let edge = row == 0 || column == 0 || row == height - 1 || column == width - 1;
+                let mut neighbors = [0; 8];
+                let mut i = 0;
+                for r in row - 1..=row + 1 {
+                    for c in column - 1..=column + 1 {
+                        if (r, c) == (row, column) {
+                            continue;
+                        }
+                        neighbors[i] = if !edge {
+                            state(r, c)
+                        } else {
+                            match self.topology.wrap(self.width, self.height, r, c) {
+                                Some((r, c)) => state(r as i64, c as i64),
+                                None => 0,
+                            }
+                        };
+                        i += 1;
+                    }
+                }
+                next_cells[(row * width + column) as usize] = self.automaton.next(state(row, column), &neighbors);
+            }
+        }
+        self.next = std::mem::replace(&mut self.cells, next_cells);
+        self.generation += 1;
+    }
+
+    fn contains(&self, row: i64, column: i64) -> bool {
+        row >= 0 && column >= 0 && row < self.height as i64 && column < self.width as i64
+    }
+}
+
+impl Engine for MultiStateUniverse {
+    /// The Life-like rule of the live cells, or Conway's rule for automata like Wireworld that have none.
+    fn rule(&self) -> Rule {
+        self.automaton.life_rule().unwrap_or_default()
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        self.set_automaton(Rc::new(rule))
+    }
+
+    fn automaton(&self) -> Rc<dyn Automaton> {
+        self.automaton.clone()
+    }
+
+    /// Cells in states the new automaton does not have die.
+    fn set_automaton(&mut self, automaton: Rc<dyn Automaton>) -> Result<(), String> {
+        let states = automaton.states();
+        self.cells
+            .iter_mut()
+            .filter(|state| **state >= states)
+            .for_each(|state| *state = 0);
+        self.automaton = automaton;
+        Ok(())
+    }
+
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        if topology == Topology::Sphere && self.width != self.height {
+            return Err(format!(
+                "a sphere needs a square universe, got {}x{}",
+                self.width, self.height
+            ));
+        }
+        self.topology = topology;
+        Ok(())
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.cells.iter().filter(|state| **state != 0).count() as u64
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        (0..1u64 << k).for_each(|_| self.tick());
+    }
+
+    fn cell(&self, row: i64, column: i64) -> Cell {
+        if self.state(row, column) == 0 {
+            Cell::Dead
+        } else {
+            Cell::Alive
+        }
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell) {
+        let state = match cell {
+            Cell::Dead => 0,
+            Cell::Alive => self.automaton.live_state(),
+        };
+        self.set_state(row, column, state);
+    }
+
+    fn state(&self, row: i64, column: i64) -> u8 {
+        if !self.contains(row, column) {
+            return 0;
+        }
+        self.get(row as u32, column as u32)
+    }
+
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        if self.contains(row, column) {
+            self.set(row as u32, column as u32, state);
+        }
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        self.cell_states().into_iter().map(|(cell, _)| cell).collect()
+    }
+
+    fn cell_states(&self) -> Vec<((i64, i64), u8)> {
+        let width = self.width as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state != 0)
+            .map(|(i, state)| (((i / width) as i64, (i % width) as i64), *state))
+            .collect()
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.cells.len() + self.next.len()
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        Some((0, 0, self.height as i64 - 1, self.width as i64 - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::game_of_life::{Cell, Engine, MultiStateUniverse, Rule, Topology, Universe};
+
+    #[test]
+    fn from_engine_keeps_the_grid() {
+        let mut universe = Universe::empty(100, 40);
+        universe.set_topology(Topology::KleinBottle).unwrap();
+        universe.set(39, 99, Cell::Alive);
+        let multistate = MultiStateUniverse::from_engine(&universe, Rc::new(Rule::default()));
+        assert_eq!((multistate.width, multistate.height), (100, 40));
+        assert_eq!(multistate.topology(), Topology::KleinBottle);
+        assert_eq!(multistate.live_cells(), [(39, 99)]);
+    }
+}
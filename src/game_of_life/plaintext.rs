@@ -0,0 +1,84 @@
+use std::fmt::Write;
+
+use crate::game_of_life::{Cell, Universe};
+
+impl Universe {
+    /// Parses a pattern in the plaintext format, see
+    /// https://conwaylife.com/wiki/Plaintext
+    pub fn from_cells(source: &str) -> Result<Universe, String> {
+        let mut name = None;
+        let mut comments = vec![];
+        let mut rows: Vec<Vec<Cell>> = vec![];
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.trim_end();
+            if let Some(comment) = line.strip_prefix('!') {
+                match comment.strip_prefix("Name:") {
+                    Some(text) => name = Some(text.trim().to_string()),
+                    None => comments.push(comment.trim().to_string()),
+                }
+                continue;
+            }
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    '.' => Ok(Cell::Dead),
+                    'O' | '*' => Ok(Cell::Alive),
+                    c => Err(format!("line {}: unexpected character `{}`", line_number + 1, c)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+        let mut universe = Universe::empty(width, rows.len() as u32);
+        universe.name = name;
+        universe.comments = comments;
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                universe.set(row as u32, col as u32, *cell);
+            }
+        }
+        Ok(universe)
+    }
+
+    pub fn to_cells(&self) -> String {
+        let mut output = String::new();
+        if let Some(name) = &self.name {
+            writeln!(output, "!Name: {}", name).unwrap();
+        }
+        self.comments.iter().for_each(|comment| {
+            writeln!(output, "!{}", comment).unwrap();
+        });
+        for row in 0..self.height {
+            (0..self.width).for_each(|col| {
+                output.push(match self.get(row, col) {
+                    Cell::Dead => '.',
+                    Cell::Alive => 'O',
+                });
+            });
+            output.push('\n');
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::Universe;
+
+    #[test]
+    fn round_trips() {
+        let source = "!Name: Glider\n!A small spaceship\n.O.\n..O\nOOO\n";
+        assert_eq!(Universe::from_cells(source).unwrap().to_cells(), source);
+    }
+
+    #[test]
+    fn converts_to_rle_and_back() {
+        let universe = Universe::from_cells(".O.\n..O\nOOO\n").unwrap();
+        let converted = Universe::from_rle(&universe.to_rle()).unwrap();
+        assert_eq!(converted.to_cells(), universe.to_cells());
+        let converted = Universe::from_life106(&universe.to_life106()).unwrap();
+        assert_eq!(converted.to_cells(), universe.to_cells());
+    }
+}
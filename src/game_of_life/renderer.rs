@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use web_sys::CanvasRenderingContext2d;
+
+use crate::dom;
+use crate::game_of_life::Canvas;
+
+/// Draws the cells of the view of a canvas, through its `Viewport`.
+pub trait Renderer {
+    fn render(&mut self, canvas: &Canvas);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RendererKind {
+    /// `Canvas2dRenderer`, one `fillRect` per live block.
+    Canvas2d,
+    /// `GlRenderer`, one texture upload and one draw call per frame.
+    WebGl,
+}
+
+impl FromStr for RendererKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2d" => Ok(RendererKind::Canvas2d),
+            "webgl" => Ok(RendererKind::WebGl),
+            _ => Err(format!("unknown renderer `{}`, expected `2d` or `webgl`", s)),
+        }
+    }
+}
+
+/// The smallest size of a highlighted cell, in pixels.
+const MIN_HIGHLIGHT_SIZE: f64 = 3.0;
+
+pub struct Canvas2dRenderer {
+    grid_color: &'static str,
+    selection_color: &'static str,
+    highlight_color: &'static str,
+    /// The palette of the last frame, and the CSS color of every shade of it.
+    palette: Vec<[u8; 3]>,
+    css_palette: Vec<String>,
+    ctx: CanvasRenderingContext2d,
+    /// The blocks of the view, reused between frames.
+    texels: Vec<u8>,
+}
+
+impl Canvas2dRenderer {
+    pub fn new() -> Canvas2dRenderer {
+        let canvas = dom::canvas("canvas");
+        Canvas2dRenderer {
+            grid_color: "#CCCCCC",
+            palette: vec![],
+            css_palette: vec![],
+            selection_color: "rgba(61, 103, 166, 0.3)",
+            highlight_color: "#E0301E",
+            ctx: dom::canvas_context::<CanvasRenderingContext2d>(&canvas, "2d"),
+            texels: vec![],
+        }
+    }
+
+    fn draw_cells(&mut self, canvas: &Canvas) {
+        let palette = canvas.palette();
+        if self.palette != palette {
+            self.css_palette = palette
+                .iter()
+                .map(|[r, g, b]| format!("rgb({}, {}, {})", r, g, b))
+                .collect();
+            self.palette = palette;
+        }
+
+        let viewport = &canvas.viewport;
+        self.ctx.set_fill_style_str(&self.css_palette[0]);
+        self.ctx
+            .fill_rect(0.0, 0.0, viewport.width.into(), viewport.height.into());
+
+        let blocks = canvas.blocks(&mut self.texels);
+        let mut fill_shade = 0;
+        for (i, texel) in self.texels.iter().enumerate() {
+            if *texel == 0 {
+                continue;
+            }
+            if *texel != fill_shade {
+                self.ctx.set_fill_style_str(&self.css_palette[*texel as usize]);
+                fill_shade = *texel;
+            }
+            let row = (blocks.top + (i / blocks.columns as usize) as i64) * blocks.size;
+            let column = (blocks.left + (i % blocks.columns as usize) as i64) * blocks.size;
+            // Rounded so that neighbouring blocks leave no gaps
+            let (x0, y0) = viewport.pixel_at((row, column));
+            let (x1, y1) = viewport.pixel_at((row + blocks.size, column + blocks.size));
+            let (x0, y0) = (x0.round(), y0.round());
+            self.ctx.fill_rect(x0, y0, x1.round() - x0, y1.round() - y0);
+        }
+    }
+
+    fn draw_grid(&self, canvas: &Canvas) {
+        let viewport = &canvas.viewport;
+        let (top, left) = viewport.cell_at(0.0, 0.0);
+        let (bottom, right) = viewport.cell_at(viewport.width.into(), viewport.height.into());
+        self.ctx.begin_path();
+        self.ctx.set_stroke_style_str(self.grid_color);
+        (left..=right + 1).for_each(|column| {
+            let x = viewport.pixel_at((top, column)).0.ceil() + 0.5;
+            self.ctx.move_to(x, 0.0);
+            self.ctx.line_to(x, viewport.height.into());
+        });
+        (top..=bottom + 1).for_each(|row| {
+            let y = viewport.pixel_at((row, left)).1.ceil() + 0.5;
+            self.ctx.move_to(0.0, y);
+            self.ctx.line_to(viewport.width.into(), y);
+        });
+        self.ctx.stroke();
+    }
+
+    fn draw_highlights(&self, canvas: &Canvas) {
+        let viewport = &canvas.viewport;
+        self.ctx.set_fill_style_str(self.highlight_color);
+        for (row, column) in canvas.engine.highlights() {
+            let (x0, y0) = viewport.pixel_at((row, column));
+            let (x1, y1) = viewport.pixel_at((row + 1, column + 1));
+            // Highlights stay visible when cells are smaller than a pixel
+            let size = (x1 - x0).max(MIN_HIGHLIGHT_SIZE);
+            let (x, y) = ((x0 + x1 - size) / 2.0, (y0 + y1 - size) / 2.0);
+            self.ctx.fill_rect(x.round(), y.round(), size.round(), size.round());
+        }
+    }
+
+    fn draw_selection(&self, canvas: &Canvas) {
+        if let Some((x, y, width, height)) = canvas.selection_rect() {
+            self.ctx.set_fill_style_str(self.selection_color);
+            self.ctx.fill_rect(x, y, width, height);
+        }
+    }
+}
+
+impl Renderer for Canvas2dRenderer {
+    fn render(&mut self, canvas: &Canvas) {
+        self.draw_cells(canvas);
+        if canvas.grid && canvas.viewport.show_grid() {
+            self.draw_grid(canvas);
+        }
+        if canvas.highlight {
+            self.draw_highlights(canvas);
+        }
+        self.draw_selection(canvas);
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt::Write;
+
+use crate::game_of_life::{Cell, Rule, Topology, Universe, MAX_PATTERN_CELLS};
+
+const MAX_LINE_LENGTH: usize = 70;
+
+impl Universe {
+    /// Parses a pattern in the Run Length Encoded format, see
+    /// https://conwaylife.com/wiki/Run_Length_Encoded
+    pub fn from_rle(source: &str) -> Result<Universe, String> {
+        let mut name = None;
+        let mut comments = vec![];
+        let mut header = None;
+        let mut data = String::new();
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if header.is_none() {
+                if line.is_empty() {
+                    continue;
+                }
+                if let Some(comment) = line.strip_prefix('#') {
+                    let (tag, text) = comment.split_at(comment.chars().next().map_or(0, char::len_utf8));
+                    match tag {
+                        "N" => name = Some(text.trim().to_string()),
+                        "C" | "c" => comments.push(text.trim().to_string()),
+                        _ => {}
+                    }
+                    continue;
+                }
+                header = Some(parse_header(line).map_err(|e| format!("line {}: {}", line_number + 1, e))?);
+            } else if !line.starts_with('#') {
+                // Golly writes comments after the header too
+                data.push_str(line);
+            }
+        }
+
+        let (width, height, rule, topology) = header.ok_or_else(|| String::from("missing `x = .., y = ..` header"))?;
+        if width as u64 * height as u64 > MAX_PATTERN_CELLS {
+            return Err(format!(
+                "pattern is too large, {}x{} is more than {} cells",
+                width, height, MAX_PATTERN_CELLS
+            ));
+        }
+        let mut universe = Universe::empty(width, height);
+        universe.rule = rule;
+        if let Some(topology) = topology {
+            universe.set_topology(topology)?;
+        }
+        universe.name = name;
+        universe.comments = comments;
+
+        let (mut row, mut col) = (0u32, 0u32);
+        let mut count: Option<u32> = None;
+        for c in data.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap();
+                    count = Some(
+                        count
+                            .unwrap_or(0)
+                            .checked_mul(10)
+                            .and_then(|n| n.checked_add(digit))
+                            .ok_or_else(|| String::from("run count overflow"))?,
+                    );
+                }
+                'b' | 'o' => {
+                    let run = count.take().unwrap_or(1);
+                    if col.saturating_add(run) > width || row >= height {
+                        return Err(format!(
+                            "row {} is larger than the {}x{} pattern size in the header",
+                            row + 1,
+                            width,
+                            height
+                        ));
+                    }
+                    if c == 'o' {
+                        (col..col + run).for_each(|col| universe.set(row, col, Cell::Alive));
+                    }
+                    col += run;
+                }
+                '$' => {
+                    row = row.checked_add(count.take().unwrap_or(1)).ok_or_else(|| {
+                        format!(
+                            "row {} is larger than the {}x{} pattern size in the header",
+                            row as u64 + 2,
+                            width,
+                            height
+                        )
+                    })?;
+                    col = 0;
+                }
+                '!' => return Ok(universe),
+                c if c.is_whitespace() => {}
+                c => return Err(format!("unexpected character `{}` in pattern data", c)),
+            }
+        }
+
+        Ok(universe)
+    }
+
+    pub fn to_rle(&self) -> String {
+        let mut output = String::new();
+        if let Some(name) = &self.name {
+            writeln!(output, "#N {}", name).unwrap();
+        }
+        self.comments.iter().for_each(|comment| {
+            writeln!(output, "#C {}", comment).unwrap();
+        });
+        writeln!(output, "x = {}, y = {}, rule = {}", self.width, self.height, self.rule).unwrap();
+
+        let mut runs = vec![];
+        let mut current_row = 0;
+        for row in 0..self.height {
+            let cells = (0..self.width).map(|col| self.get(row, col));
+            let mut row_runs: Vec<(u32, char)> = vec![];
+            for cell in cells {
+                let tag = match cell {
+                    Cell::Dead => 'b',
+                    Cell::Alive => 'o',
+                };
+                match row_runs.last_mut() {
+                    Some((n, last)) if *last == tag => *n += 1,
+                    _ => row_runs.push((1, tag)),
+                }
+            }
+            if let Some((_, 'b')) = row_runs.last() {
+                row_runs.pop();
+            }
+            if row_runs.is_empty() {
+                continue;
+            }
+            if row > current_row {
+                runs.push((row - current_row, '$'));
+                current_row = row;
+            }
+            runs.extend(row_runs);
+        }
+        runs.push((1, '!'));
+
+        let mut line = String::new();
+        for (n, tag) in runs {
+            let item = if n == 1 {
+                tag.to_string()
+            } else {
+                format!("{}{}", n, tag)
+            };
+            if line.len() + item.len() > MAX_LINE_LENGTH {
+                writeln!(output, "{}", line).unwrap();
+                line.clear();
+            }
+            line.push_str(&item);
+        }
+        writeln!(output, "{}", line).unwrap();
+        output
+    }
+}
+
+fn parse_header(line: &str) -> Result<(u32, u32, Rule, Option<Topology>), String> {
+    let mut width = None;
+    let mut height = None;
+    let mut rule = Rule::default();
+    let mut topology = None;
+    // Golly appends the bounded grid to the rule, like `B3/S23:T64,64`, which contains a comma
+    let (line, grid) = match line.find(':') {
+        Some(i) => (&line[..i], Some(&line[i + 1..])),
+        None => (line, None),
+    };
+    if let Some(grid) = grid {
+        let letter = grid.get(..1).unwrap_or("");
+        topology = Some(letter.parse().map_err(|_| format!("unknown bounded grid `{}`", grid))?);
+    }
+
+    for entry in line.split(',') {
+        let mut parts = entry.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("expected `key = value` in header, found `{}`", entry.trim()))?
+            .trim();
+        match key {
+            "x" => width = Some(parse_dimension(value)?),
+            "y" => height = Some(parse_dimension(value)?),
+            "rule" => rule = value.parse()?,
+            _ => return Err(format!("unknown header key `{}`", key)),
+        }
+    }
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule, topology)),
+        _ => Err(String::from("header must contain both `x` and `y`")),
+    }
+}
+
+fn parse_dimension(value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid pattern dimension `{}`", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::{Cell, Universe};
+
+    const GLIDER: &str = "#N Glider\n#C A small spaceship\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+
+    #[test]
+    fn round_trips() {
+        let universe = Universe::from_rle(GLIDER).unwrap();
+        assert_eq!(universe.name.as_deref(), Some("Glider"));
+        assert_eq!(universe.get(0, 1), Cell::Alive);
+        assert_eq!(universe.get(0, 0), Cell::Dead);
+        assert_eq!(universe.to_rle(), GLIDER);
+    }
+
+    #[test]
+    fn skips_comments_after_the_header() {
+        let universe = Universe::from_rle("x = 3, y = 1\n#C written by Golly\n3o!\n").unwrap();
+        assert_eq!(universe.to_rle(), "x = 3, y = 1, rule = B3/S23\n3o!\n");
+    }
+
+    #[test]
+    fn rejects_huge_headers() {
+        let error = Universe::from_rle("x = 100000, y = 100000\n!").err().unwrap();
+        assert!(error.starts_with("pattern is too large"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_rows_past_u32() {
+        let error = Universe::from_rle("x = 1, y = 1\n4294967295$4294967295$!")
+            .err()
+            .unwrap();
+        assert!(error.starts_with("row "), "{}", error);
+    }
+}
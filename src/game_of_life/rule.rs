@@ -0,0 +1,118 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_of_life::Cell;
+
+/// A Life-like rule, see https://conwaylife.com/wiki/Life-like_cellular_automaton
+///
+/// `birth` and `survival` are bit sets over the number of live neighbours, bit `n` is set when a
+/// dead cell with `n` live neighbours is born, or a live cell with `n` live neighbours survives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: u16,
+    pub survival: u16,
+}
+
+impl Rule {
+    pub const CONWAY: Rule = Rule {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+    };
+
+    pub fn next(&self, cell: Cell, live_neighbors: u8) -> Cell {
+        let set = match cell {
+            Cell::Dead => self.birth,
+            Cell::Alive => self.survival,
+        };
+        if set & (1 << live_neighbors) != 0 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    /// The next state of 64 bit-packed cells, given their 8 neighbours shifted into place, counting
+    /// the live neighbours of all of them at once with bit-sliced adders.
+    pub fn next_cells(&self, cells: u64, neighbors: [u64; 8]) -> u64 {
+        let mut count = [0; 4];
+        for neighbor in neighbors.iter() {
+            let mut carry = *neighbor;
+            for bit in count.iter_mut() {
+                let next_carry = *bit & carry;
+                *bit ^= carry;
+                carry = next_carry;
+            }
+        }
+
+        let mut next = 0;
+        for n in 0..=8 {
+            let born = self.birth & (1 << n) != 0;
+            let survives = self.survival & (1 << n) != 0;
+            if !born && !survives {
+                continue;
+            }
+            let has_n_neighbors = (0..4).fold(!0, |mask, k| {
+                mask & if n & (1 << k) != 0 { count[k] } else { !count[k] }
+            });
+            if born {
+                next |= has_n_neighbors & !cells;
+            }
+            if survives {
+                next |= has_n_neighbors & cells;
+            }
+        }
+        next
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses `B3/S23` notation, or the older `23/3` survival/birth notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut parts = s.split('/');
+        let (first, second) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(first), Some(second), None) => (first.trim(), second.trim()),
+            _ => return Err(format!("invalid rule `{}`, expected `B3/S23` or `23/3`", s)),
+        };
+
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B'), _) | (Some('b'), _) => (first, second),
+            (Some('S'), _) | (Some('s'), _) => (second, first),
+            _ => (second, first),
+        };
+        let birth = birth.strip_prefix(|c| c == 'B' || c == 'b').unwrap_or(birth);
+        let survival = survival.strip_prefix(|c| c == 'S' || c == 's').unwrap_or(survival);
+
+        Ok(Rule {
+            birth: parse_counts(birth, s)?,
+            survival: parse_counts(survival, s)?,
+        })
+    }
+}
+
+fn parse_counts(counts: &str, rule: &str) -> Result<u16, String> {
+    counts.chars().try_fold(0, |set, c| match c.to_digit(10) {
+        Some(n) if n <= 8 => Ok(set | 1 << n),
+        _ => Err(format!("invalid neighbour count `{}` in rule `{}`", c, rule)),
+    })
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |set: u16| {
+            (0..=8)
+                .filter(|n| set & (1 << n) != 0)
+                .map(|n| n.to_string())
+                .collect::<String>()
+        };
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+use std::hash::Hasher;
+use std::str::FromStr;
+
+use crate::game_of_life::{Cell, Fnv1a, Random, Universe};
+
+/// The symmetry of a random soup, see https://conwaylife.com/wiki/Symmetry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// No symmetry.
+    C1,
+    /// Mirrored left to right.
+    D2,
+    /// Mirrored left to right and top to bottom.
+    D4,
+    /// Mirrored left to right, top to bottom and along the diagonal.
+    D8,
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "C1" => Ok(Symmetry::C1),
+            "D2" => Ok(Symmetry::D2),
+            "D4" => Ok(Symmetry::D4),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err(format!("unknown symmetry `{}`, expected `C1`, `D2`, `D4` or `D8`", s)),
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Densities are probabilities for every cell to be alive, from 0 to 1.
+pub fn check_density(density: f64) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&density) {
+        return Err(format!("the density of a soup is from 0 to 1, got {}", density));
+    }
+    Ok(())
+}
+
+/// A `size x size` soup of cells alive with the given density.
+pub fn soup(random: &mut Random, size: u32, density: f64, symmetry: Symmetry) -> Universe {
+    let mut universe = Universe::empty(size, size);
+    let last = size.saturating_sub(1);
+    universe
+        .fill_soup(random, (0, 0, last, last), density, symmetry)
+        .unwrap();
+    universe
+}
+
+impl Universe {
+    /// A universe of the given size filled with a soup of the given density, the same seed always gives
+    /// the same soup.
+    pub fn random(width: u32, height: u32, seed: &str, density: f64, symmetry: Symmetry) -> Result<Universe, String> {
+        let mut universe = Universe::empty(width, height);
+        if width > 0 && height > 0 {
+            let mut random = Random::new(soup_seed(seed, 0));
+            universe.fill_soup(&mut random, (0, 0, height - 1, width - 1), density, symmetry)?;
+        }
+        Ok(universe)
+    }
+
+    /// Fills the `(top, left, bottom, right)` cells with a soup, the cells outside are unchanged.
+    ///
+    /// Only the cells of the fundamental region of the symmetry are random, the other cells are mirrored
+    /// from them, `D8` needs a square.
+    pub fn fill_soup(
+        &mut self,
+        random: &mut Random,
+        (top, left, bottom, right): (u32, u32, u32, u32),
+        density: f64,
+        symmetry: Symmetry,
+    ) -> Result<(), String> {
+        check_density(density)?;
+        if top > bottom || left > right || bottom >= self.height || right >= self.width {
+            return Err(format!(
+                "the rectangle ({}, {}, {}, {}) is outside of the {}x{} universe",
+                top, left, bottom, right, self.width, self.height
+            ));
+        }
+        let (height, width) = (bottom - top + 1, right - left + 1);
+        if symmetry == Symmetry::D8 && width != height {
+            return Err(format!("a D8 soup has to be square, got {}x{}", width, height));
+        }
+        let (last_row, last_col) = (height - 1, width - 1);
+        for row in 0..height {
+            for col in 0..width {
+                let (source_row, source_col) = match symmetry {
+                    Symmetry::C1 => (row, col),
+                    Symmetry::D2 => (row, col.min(last_col - col)),
+                    Symmetry::D4 => (row.min(last_row - row), col.min(last_col - col)),
+                    Symmetry::D8 => {
+                        let (row, col) = (row.min(last_row - row), col.min(last_col - col));
+                        (row.min(col), row.max(col))
+                    }
+                };
+                let cell = if (source_row, source_col) == (row, col) {
+                    if random.next_f64() < density {
+                        Cell::Alive
+                    } else {
+                        Cell::Dead
+                    }
+                } else {
+                    self.get(top + source_row, left + source_col)
+                };
+                self.set(top + row, left + col, cell);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The seed of the soup at `index` of a search, soups can be reproduced from the seed of the search
+/// and their index alone.
+pub fn soup_seed(seed: &str, index: u64) -> u64 {
+    let mut hasher = Fnv1a::default();
+    hasher.write(seed.as_bytes());
+    hasher.write_u64(index);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_of_life::{Symmetry, Universe};
+
+    #[test]
+    fn rejects_densities_outside_0_to_1() {
+        for density in [-0.1, 1.5, f64::NAN] {
+            assert!(Universe::random(8, 8, "seed", density, Symmetry::C1).is_err());
+        }
+        assert!(Universe::random(8, 8, "seed", 1.0, Symmetry::C1).is_ok());
+    }
+}
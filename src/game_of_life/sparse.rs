@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::mem;
+
+use crate::game_of_life::{bit_range, check_unbounded_rule, shifted, Cell, Engine, Rule, Topology};
+
+const TILE_SIZE: i64 = 64;
+
+/// 64x64 cells, one word per row.
+type Tile = [u64; TILE_SIZE as usize];
+
+/// An unbounded universe that only allocates the 64x64 tiles containing live cells.
+///
+/// Tiles are keyed by `(row, column)` of the tile, tile `(0, 0)` covers cells `(0, 0)` to
+/// `(63, 63)`, and empty tiles are freed after every generation.
+#[derive(Clone)]
+pub struct SparseUniverse {
+    rule: Rule,
+    generation: u64,
+    tiles: HashMap<(i64, i64), Tile>,
+}
+
+impl SparseUniverse {
+    pub fn new(rule: Rule) -> Result<SparseUniverse, String> {
+        check_unbounded_rule(rule)?;
+        Ok(SparseUniverse {
+            rule,
+            generation: 0,
+            tiles: HashMap::new(),
+        })
+    }
+
+    pub fn tick(&mut self) {
+        let mut candidates = vec![];
+        for (&(tile_row, tile_col), tile) in self.tiles.iter() {
+            candidates.push((tile_row, tile_col));
+            let north = tile[0] != 0;
+            let south = tile[TILE_SIZE as usize - 1] != 0;
+            let west = tile.iter().any(|row| row & 1 != 0);
+            let east = tile.iter().any(|row| row >> 63 != 0);
+            let neighbors = [
+                (north && west, -1, -1),
+                (north, -1, 0),
+                (north && east, -1, 1),
+                (west, 0, -1),
+                (east, 0, 1),
+                (south && west, 1, -1),
+                (south, 1, 0),
+                (south && east, 1, 1),
+            ];
+            for (touches, delta_row, delta_col) in neighbors.iter() {
+                let neighbor = (tile_row + delta_row, tile_col + delta_col);
+                if *touches && !self.tiles.contains_key(&neighbor) {
+                    candidates.push(neighbor);
+                }
+            }
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        let mut tiles = HashMap::with_capacity(candidates.len());
+        for (tile_row, tile_col) in candidates {
+            let next = self.next_tile(tile_row, tile_col);
+            if next.iter().any(|row| *row != 0) {
+                tiles.insert((tile_row, tile_col), next);
+            }
+        }
+        self.tiles = tiles;
+        self.generation += 1;
+    }
+
+    fn next_tile(&self, tile_row: i64, tile_col: i64) -> Tile {
+        let empty = [0; TILE_SIZE as usize];
+        let tile = |delta_row: i64, delta_col: i64| {
+            self.tiles
+                .get(&(tile_row + delta_row, tile_col + delta_col))
+                .unwrap_or(&empty)
+        };
+        let (north_west, north, north_east) = (tile(-1, -1), tile(-1, 0), tile(-1, 1));
+        let (west, current, east) = (tile(0, -1), tile(0, 0), tile(0, 1));
+        let (south_west, south, south_east) = (tile(1, -1), tile(1, 0), tile(1, 1));
+
+        let last = TILE_SIZE as usize - 1;
+        // Row `r` of the tile with the cells just outside of it on either side.
+        let row = |r: i64| match r {
+            -1 => (north[last], north_west[last] >> 63, north_east[last] & 1),
+            TILE_SIZE => (south[0], south_west[0] >> 63, south_east[0] & 1),
+            r => (current[r as usize], west[r as usize] >> 63, east[r as usize] & 1),
+        };
+
+        let mut next = [0; TILE_SIZE as usize];
+        for (r, next) in next.iter_mut().enumerate() {
+            let r = r as i64;
+            let (above, above_west_edge, above_east_edge) = row(r - 1);
+            let (cells, west_edge, east_edge) = row(r);
+            let (below, below_west_edge, below_east_edge) = row(r + 1);
+            let (above_west, above_east) = shifted(&[above], 0, 64, (above_west_edge, above_east_edge));
+            let (west, east) = shifted(&[cells], 0, 64, (west_edge, east_edge));
+            let (below_west, below_east) = shifted(&[below], 0, 64, (below_west_edge, below_east_edge));
+            *next = self.rule.next_cells(
+                cells,
+                [above_west, above, above_east, west, east, below_west, below, below_east],
+            );
+        }
+        next
+    }
+}
+
+impl Engine for SparseUniverse {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        check_unbounded_rule(rule)?;
+        self.rule = rule;
+        Ok(())
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        Err(format!(
+            "the sparse universe is unbounded, it does not support {:?}",
+            topology
+        ))
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.tiles
+            .values()
+            .map(|tile| tile.iter().map(|row| row.count_ones() as u64).sum::<u64>())
+            .sum()
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        (0..1u64 << k).for_each(|_| self.tick());
+    }
+
+    fn cell(&self, row: i64, column: i64) -> Cell {
+        let (tile, r, bit) = split(row, column);
+        match self.tiles.get(&tile) {
+            Some(tile) if tile[r] & (1 << bit) != 0 => Cell::Alive,
+            _ => Cell::Dead,
+        }
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell) {
+        let (key, r, bit) = split(row, column);
+        match cell {
+            Cell::Alive => self.tiles.entry(key).or_insert([0; TILE_SIZE as usize])[r] |= 1 << bit,
+            Cell::Dead => {
+                if let Some(tile) = self.tiles.get_mut(&key) {
+                    tile[r] &= !(1 << bit);
+                    if tile.iter().all(|row| *row == 0) {
+                        self.tiles.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = vec![];
+        for (&(tile_row, tile_col), tile) in self.tiles.iter() {
+            for (r, row) in tile.iter().enumerate() {
+                let mut row = *row;
+                while row != 0 {
+                    let bit = row.trailing_zeros() as i64;
+                    cells.push((tile_row * TILE_SIZE + r as i64, tile_col * TILE_SIZE + bit));
+                    row &= row - 1;
+                }
+            }
+        }
+        cells
+    }
+
+    /// From the tiles overlapping the area, or from all tiles if there are fewer of them.
+    fn any_alive(&self, (top, left, bottom, right): (i64, i64, i64, i64)) -> bool {
+        let tile_rows = top.div_euclid(TILE_SIZE)..=bottom.div_euclid(TILE_SIZE);
+        let tile_cols = left.div_euclid(TILE_SIZE)..=right.div_euclid(TILE_SIZE);
+        let tile_alive = |(tile_row, tile_col): (i64, i64), tile: &Tile| {
+            let (row, col) = (tile_row * TILE_SIZE, tile_col * TILE_SIZE);
+            let mask = bit_range(left - col, right - col);
+            let rows = (top - row).max(0)..=(bottom - row).min(TILE_SIZE - 1);
+            rows.into_iter().any(|r| tile[r as usize] & mask != 0)
+        };
+        let overlapping =
+            (tile_rows.end() - tile_rows.start() + 1).saturating_mul(tile_cols.end() - tile_cols.start() + 1);
+        if overlapping > self.tiles.len() as i64 {
+            self.tiles
+                .iter()
+                .any(|(key, tile)| tile_rows.contains(&key.0) && tile_cols.contains(&key.1) && tile_alive(*key, tile))
+        } else {
+            tile_rows.clone().any(|tile_row| {
+                tile_cols.clone().any(|tile_col| {
+                    self.tiles
+                        .get(&(tile_row, tile_col))
+                        .is_some_and(|tile| tile_alive((tile_row, tile_col), tile))
+                })
+            })
+        }
+    }
+
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        let mut bounds: Option<(i64, i64, i64, i64)> = None;
+        for (&(tile_row, tile_col), tile) in self.tiles.iter() {
+            let rows = tile.iter().enumerate().filter(|(_, row)| **row != 0);
+            let top = rows.clone().map(|(r, _)| r).min().unwrap() as i64;
+            let bottom = rows.map(|(r, _)| r).max().unwrap() as i64;
+            let columns = tile.iter().fold(0, |columns, row| columns | row);
+            let left = columns.trailing_zeros() as i64;
+            let right = 63 - columns.leading_zeros() as i64;
+
+            let (row, col) = (tile_row * TILE_SIZE, tile_col * TILE_SIZE);
+            let tile_bounds = (row + top, col + left, row + bottom, col + right);
+            bounds = Some(match bounds {
+                None => tile_bounds,
+                Some((top, left, bottom, right)) => (
+                    top.min(tile_bounds.0),
+                    left.min(tile_bounds.1),
+                    bottom.max(tile_bounds.2),
+                    right.max(tile_bounds.3),
+                ),
+            });
+        }
+        bounds
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.tiles.len() * mem::size_of::<((i64, i64), Tile)>()
+    }
+}
+
+fn split(row: i64, column: i64) -> ((i64, i64), usize, u32) {
+    (
+        (row.div_euclid(TILE_SIZE), column.div_euclid(TILE_SIZE)),
+        row.rem_euclid(TILE_SIZE) as usize,
+        column.rem_euclid(TILE_SIZE) as u32,
+    )
+}
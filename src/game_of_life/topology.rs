@@ -0,0 +1,63 @@
+use std::str::FromStr;
+
+/// How the edges of a finite universe are glued together, see
+/// https://golly.sourceforge.io/Help/bounded.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// Everything outside of the universe is dead.
+    Plane,
+    #[default]
+    Torus,
+    /// Left and right edges are joined like a torus, top and bottom edges with a twist.
+    KleinBottle,
+    /// Both pairs of edges are joined with a twist.
+    CrossSurface,
+    /// The top edge is joined to the left edge and the bottom edge to the right edge, only for
+    /// square universes. The cells diagonally outside the corners are dead.
+    Sphere,
+}
+
+impl Topology {
+    /// Maps a cell at most one step outside of a `width x height` universe onto the cell it is
+    /// glued to, `None` when it is dead.
+    pub fn wrap(&self, width: u32, height: u32, row: i64, column: i64) -> Option<(u32, u32)> {
+        let (w, h) = (width as i64, height as i64);
+        let row_outside = row < 0 || row >= h;
+        let column_outside = column < 0 || column >= w;
+        let (row, column) = match self {
+            Topology::Plane if row_outside || column_outside => return None,
+            Topology::Plane | Topology::Torus => (row, column),
+            Topology::KleinBottle if row_outside => (row, w - 1 - column),
+            Topology::KleinBottle => (row, column),
+            Topology::CrossSurface => (
+                if column_outside { h - 1 - row } else { row },
+                if row_outside { w - 1 - column } else { column },
+            ),
+            Topology::Sphere => match (row_outside, column_outside) {
+                (true, true) => return None,
+                (true, false) if row < 0 => (column, 0),
+                (true, false) => (column, w - 1),
+                (false, true) if column < 0 => (0, row),
+                (false, true) => (h - 1, row),
+                (false, false) => (row, column),
+            },
+        };
+        Some((row.rem_euclid(h) as u32, column.rem_euclid(w) as u32))
+    }
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    /// Parses the names, or Golly's single letters `P`, `T`, `K`, `C` and `S`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "p" | "plane" => Ok(Topology::Plane),
+            "t" | "torus" => Ok(Topology::Torus),
+            "k" | "klein" | "klein-bottle" => Ok(Topology::KleinBottle),
+            "c" | "cross" | "cross-surface" => Ok(Topology::CrossSurface),
+            "s" | "sphere" => Ok(Topology::Sphere),
+            _ => Err(format!("unknown topology `{}`", s)),
+        }
+    }
+}
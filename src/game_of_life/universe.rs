@@ -0,0 +1,322 @@
+use crate::game_of_life::{Engine, Rule, Topology};
+
+/// The most cells of a universe read from a pattern file, 8 MiB of cells per generation.
+pub const MAX_PATTERN_CELLS: u64 = 1 << 26;
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Dead = 0,
+    Alive = 1,
+}
+
+/// A finite universe with edges glued according to its topology, bit-packed with 64 cells per word.
+///
+/// Every row starts at a new word, bit `i` of a word is column `64 * word + i`, and the unused
+/// bits of the last word in a row are always zero.
+#[derive(Clone)]
+pub struct Universe {
+    pub width: u32,
+    pub height: u32,
+    words_per_row: usize,
+    cells: Vec<u64>,
+    /// The next generation, swapped with `cells` after every tick.
+    next: Vec<u64>,
+    /// The rows just above and below the universe, as seen through the topology.
+    ghost_rows: Vec<u64>,
+    topology: Topology,
+    pub rule: Rule,
+    pub generation: u64,
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+}
+
+impl Universe {
+    pub fn new(width: u32, height: u32) -> Universe {
+        let mut universe = Universe::empty(width, height);
+        (0..width * height)
+            .filter(|i| i % 2 == 0 || i % 7 == 0)
+            .for_each(|i| universe.set(i / width, i % width, Cell::Alive));
+        universe
+    }
+
+    pub fn empty(width: u32, height: u32) -> Universe {
+        let words_per_row = (width as usize).div_ceil(64);
+        Universe {
+            width,
+            height,
+            words_per_row,
+            cells: vec![0; words_per_row * height as usize],
+            next: vec![0; words_per_row * height as usize],
+            ghost_rows: vec![0; words_per_row * 2],
+            topology: Topology::default(),
+            rule: Rule::default(),
+            generation: 0,
+            name: None,
+            comments: vec![],
+        }
+    }
+
+    /// Crops the live cells of `engine` into a universe of their bounding box.
+    pub fn from_engine(engine: &dyn Engine) -> Universe {
+        let (top, left, bottom, right) = engine.bounding_box().unwrap_or((0, 0, -1, -1));
+        let mut universe = Universe::empty((right - left + 1) as u32, (bottom - top + 1) as u32);
+        universe.rule = engine.rule();
+        universe.generation = engine.generation();
+        engine.live_cells().into_iter().for_each(|(row, col)| {
+            universe.set((row - top) as u32, (col - left) as u32, Cell::Alive);
+        });
+        universe
+    }
+
+    pub fn get(&self, row: u32, column: u32) -> Cell {
+        let (idx, bit) = self.get_index(row, column);
+        if self.cells[idx] & (1 << bit) != 0 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    pub fn set(&mut self, row: u32, column: u32, cell: Cell) {
+        let (idx, bit) = self.get_index(row, column);
+        match cell {
+            Cell::Dead => self.cells[idx] &= !(1 << bit),
+            Cell::Alive => self.cells[idx] |= 1 << bit,
+        }
+    }
+
+    pub fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// The bit-packed cells, row by row.
+    pub fn words(&self) -> &[u64] {
+        &self.cells
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        if topology == Topology::Sphere && self.width != self.height {
+            return Err(format!(
+                "a sphere needs a square universe, got {}x{}",
+                self.width, self.height
+            ));
+        }
+        self.topology = topology;
+        Ok(())
+    }
+
+    /// Parses a pattern in any of the supported formats: RLE, plaintext or Life 1.06.
+    pub fn from_pattern(source: &str) -> Result<Universe, String> {
+        let first_line = source
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("");
+        if first_line.starts_with("#Life 1.06") {
+            Universe::from_life106(source)
+        } else if first_line.starts_with('!') || first_line.starts_with('.') || first_line.starts_with('O') {
+            Universe::from_cells(source)
+        } else {
+            Universe::from_rle(source)
+        }
+    }
+
+    /// Copies `pattern` into this universe with its top left corner at `(row, column)`.
+    pub fn paste(&mut self, pattern: &Universe, row: u32, column: u32) -> Result<(), String> {
+        if row.saturating_add(pattern.height) > self.height || column.saturating_add(pattern.width) > self.width {
+            return Err(format!(
+                "{}x{} pattern does not fit into the {}x{} universe",
+                pattern.width, pattern.height, self.width, self.height
+            ));
+        }
+        for r in 0..pattern.height {
+            for c in 0..pattern.width {
+                self.set(row + r, column + c, pattern.get(r, c));
+            }
+        }
+        Ok(())
+    }
+
+    /// Advances one generation, 64 cells at a time.
+    pub fn tick(&mut self) {
+        self.update_ghost_rows();
+        let mut next_cells = std::mem::take(&mut self.next);
+
+        let rule = self.rule;
+        let (width, height, words_per_row) = (self.width, self.height as i64, self.words_per_row);
+        let (cells, ghost_rows) = (&self.cells, &self.ghost_rows);
+        let row = |r: i64| {
+            if r < 0 {
+                &ghost_rows[..words_per_row]
+            } else if r >= height {
+                &ghost_rows[words_per_row..]
+            } else {
+                &cells[r as usize * words_per_row..(r as usize + 1) * words_per_row]
+            }
+        };
+        let edges = |r: i64| (self.outside(r, -1), self.outside(r, width as i64));
+        let last_mask = match self.width % 64 {
+            0 => !0,
+            n => (1 << n) - 1,
+        };
+
+        for r in 0..height {
+            let (above, above_edges) = (row(r - 1), edges(r - 1));
+            let (current, current_edges) = (row(r), edges(r));
+            let (below, below_edges) = (row(r + 1), edges(r + 1));
+
+            for i in 0..words_per_row {
+                let (above_west, above_east) = shifted(above, i, width, above_edges);
+                let (west, east) = shifted(current, i, width, current_edges);
+                let (below_west, below_east) = shifted(below, i, width, below_edges);
+
+                let neighbors = [
+                    above_west, above[i], above_east, west, east, below_west, below[i], below_east,
+                ];
+                let mut next = rule.next_cells(current[i], neighbors);
+                if i == words_per_row - 1 {
+                    next &= last_mask;
+                }
+                next_cells[r as usize * words_per_row + i] = next;
+            }
+        }
+
+        self.next = std::mem::replace(&mut self.cells, next_cells);
+        self.generation += 1;
+    }
+
+    /// The cell at `(row, column)` at most one step outside of the universe, as a bit.
+    fn outside(&self, row: i64, column: i64) -> u64 {
+        match self.topology.wrap(self.width, self.height, row, column) {
+            Some((row, column)) => self.get(row, column) as u64,
+            None => 0,
+        }
+    }
+
+    fn update_ghost_rows(&mut self) {
+        let mut ghost_rows = std::mem::take(&mut self.ghost_rows);
+        ghost_rows.iter_mut().for_each(|word| *word = 0);
+        for (i, row) in [-1, self.height as i64].iter().enumerate() {
+            for col in 0..self.width {
+                ghost_rows[i * self.words_per_row + col as usize / 64] |= self.outside(*row, col as i64) << (col % 64);
+            }
+        }
+        self.ghost_rows = ghost_rows;
+    }
+
+    fn get_index(&self, row: u32, column: u32) -> (usize, u32) {
+        (row as usize * self.words_per_row + column as usize / 64, column % 64)
+    }
+}
+
+/// The bits of a word from `first` to `last` inclusive, clamped to the 64 bits of the word.
+pub(crate) fn bit_range(first: i64, last: i64) -> u64 {
+    let (first, last) = (first.max(0), last.min(63));
+    if first > last {
+        return 0;
+    }
+    (u64::MAX >> (63 - last)) & (u64::MAX << first)
+}
+
+/// The west and east neighbours of the cells in word `i` of `row`, `edges` are the cells just
+/// outside of the row on either side.
+pub(crate) fn shifted(row: &[u64], i: usize, width: u32, (west_edge, east_edge): (u64, u64)) -> (u64, u64) {
+    let last = row.len() - 1;
+    let last_bit = (width - 1) % 64;
+    let west_carry = if i == 0 { west_edge } else { row[i - 1] >> 63 };
+    let east_carry = if i == last {
+        east_edge << last_bit
+    } else {
+        row[i + 1] << 63
+    };
+    (row[i] << 1 | west_carry, row[i] >> 1 | east_carry)
+}
+
+impl Engine for Universe {
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        self.rule = rule;
+        Ok(())
+    }
+
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        Universe::set_topology(self, topology)
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.cells.iter().map(|word| word.count_ones() as u64).sum()
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        (0..1u64 << k).for_each(|_| self.tick());
+    }
+
+    fn cell(&self, row: i64, column: i64) -> Cell {
+        if row < 0 || column < 0 || row >= self.height as i64 || column >= self.width as i64 {
+            return Cell::Dead;
+        }
+        self.get(row as u32, column as u32)
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell) {
+        if row < 0 || column < 0 || row >= self.height as i64 || column >= self.width as i64 {
+            return;
+        }
+        self.set(row as u32, column as u32, cell);
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        let mut cells = vec![];
+        for (idx, word) in self.cells.iter().enumerate() {
+            let mut word = *word;
+            while word != 0 {
+                let bit = word.trailing_zeros() as usize;
+                let (row, i) = (idx / self.words_per_row, idx % self.words_per_row);
+                cells.push((row as i64, (i * 64 + bit) as i64));
+                word &= word - 1;
+            }
+        }
+        cells
+    }
+
+    /// A word at a time.
+    fn any_alive(&self, (top, left, bottom, right): (i64, i64, i64, i64)) -> bool {
+        let (top, left) = (top.max(0), left.max(0));
+        let (bottom, right) = (bottom.min(self.height as i64 - 1), right.min(self.width as i64 - 1));
+        if top > bottom || left > right {
+            return false;
+        }
+        (top as usize..=bottom as usize).any(|row| {
+            let words = &self.cells[row * self.words_per_row..(row + 1) * self.words_per_row];
+            (left / 64..=right / 64).any(|i| words[i as usize] & bit_range(left - i * 64, right - i * 64) != 0)
+        })
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    fn memory_usage(&self) -> usize {
+        (self.cells.len() + self.next.len() + self.ghost_rows.len()) * std::mem::size_of::<u64>()
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        Some((0, 0, self.height as i64 - 1, self.width as i64 - 1))
+    }
+}
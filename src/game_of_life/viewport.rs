@@ -0,0 +1,114 @@
+/// The smallest and largest size of a cell in pixels.
+pub const MIN_SCALE: f64 = 1.0 / 1024.0;
+pub const MAX_SCALE: f64 = 64.0;
+/// Cells smaller than this many pixels are drawn without the grid.
+pub const MIN_GRID_SCALE: f64 = 4.0;
+
+/// Maps the pixels of the canvas to cells, the cell `(row, column)` has its top left corner at
+/// `((column - origin.1) * scale, (row - origin.0) * scale)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    /// The `(row, column)` at the top left corner of the canvas, in fractions of cells.
+    pub origin: (f64, f64),
+    /// The size of a cell in pixels.
+    pub scale: f64,
+    /// The size of the canvas in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The square blocks of cells of a viewport that are drawn as one texel each, a block has `size`
+/// cells along each side and blocks start at multiples of `size`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Blocks {
+    pub size: i64,
+    /// The `(row, column)` of the top left block, in blocks.
+    pub top: i64,
+    pub left: i64,
+    pub rows: u32,
+    pub columns: u32,
+}
+
+impl Viewport {
+    pub fn new(width: u32, height: u32, scale: f64) -> Viewport {
+        Viewport {
+            origin: (0.0, 0.0),
+            scale,
+            width,
+            height,
+        }
+    }
+
+    /// The `(row, column)` of the cell at the given pixel offset.
+    pub fn cell_at(&self, x: f64, y: f64) -> (i64, i64) {
+        (
+            (self.origin.0 + y / self.scale).floor() as i64,
+            (self.origin.1 + x / self.scale).floor() as i64,
+        )
+    }
+
+    /// The `(x, y)` offset in pixels of the top left corner of a cell.
+    pub fn pixel_at(&self, (row, column): (i64, i64)) -> (f64, f64) {
+        (
+            (column as f64 - self.origin.1) * self.scale,
+            (row as f64 - self.origin.0) * self.scale,
+        )
+    }
+
+    /// The `(row, column)` at the center of the canvas.
+    pub fn center(&self) -> (f64, f64) {
+        (
+            self.origin.0 + self.height as f64 / 2.0 / self.scale,
+            self.origin.1 + self.width as f64 / 2.0 / self.scale,
+        )
+    }
+
+    /// Moves the cells by the given number of pixels.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.origin.0 -= dy / self.scale;
+        self.origin.1 -= dx / self.scale;
+    }
+
+    /// Scales the cells by `factor`, keeping the cell under the pixel `(x, y)` in place.
+    pub fn zoom(&mut self, x: f64, y: f64, factor: f64) {
+        let (row, column) = (self.origin.0 + y / self.scale, self.origin.1 + x / self.scale);
+        self.scale = (self.scale * factor).clamp(MIN_SCALE, MAX_SCALE);
+        self.origin = (row - y / self.scale, column - x / self.scale);
+    }
+
+    /// Zooms and pans so that the `(top, left, bottom, right)` cells fill most of the canvas.
+    pub fn fit(&mut self, (top, left, bottom, right): (i64, i64, i64, i64)) {
+        let (rows, columns) = ((bottom - top + 1) as f64, (right - left + 1) as f64);
+        let scale = (self.width as f64 / columns).min(self.height as f64 / rows) * 0.9;
+        self.scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+        self.origin = (
+            top as f64 + rows / 2.0 - self.height as f64 / 2.0 / self.scale,
+            left as f64 + columns / 2.0 - self.width as f64 / 2.0 / self.scale,
+        );
+    }
+
+    pub fn show_grid(&self) -> bool {
+        self.scale >= MIN_GRID_SCALE
+    }
+
+    /// The blocks covering the canvas, blocks are single cells unless cells are smaller than a pixel,
+    /// then they are the smallest power of two cells that cover a pixel.
+    pub fn blocks(&self) -> Blocks {
+        let size = if self.scale >= 1.0 {
+            1
+        } else {
+            (1.0 / self.scale).log2().ceil().exp2() as i64
+        };
+        let block = |cell: f64| (cell / size as f64).floor() as i64;
+        let (top, left) = (block(self.origin.0), block(self.origin.1));
+        let bottom = block(self.origin.0 + self.height as f64 / self.scale);
+        let right = block(self.origin.1 + self.width as f64 / self.scale);
+        Blocks {
+            size,
+            top,
+            left,
+            rows: (bottom - top + 1) as u32,
+            columns: (right - left + 1) as u32,
+        }
+    }
+}
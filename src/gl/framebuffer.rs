@@ -0,0 +1,64 @@
+use wasm_bindgen::JsValue;
+use web_sys::{WebGlFramebuffer, WebGlRenderingContext};
+
+use crate::gl::Texture;
+
+/// An RGBA texture that programs render into instead of the canvas.
+pub struct Framebuffer {
+    pub framebuffer: WebGlFramebuffer,
+    pub texture: Texture,
+    pub size: (u32, u32),
+}
+
+impl Framebuffer {
+    /// Allocates the texture on the given unit, with `data` as its initial texels or zeros.
+    pub fn new(
+        gl: &WebGlRenderingContext,
+        unit: u32,
+        (width, height): (u32, u32),
+        data: Option<&[u8]>,
+    ) -> Result<Framebuffer, JsValue> {
+        let texture = Texture::new(gl, unit)?;
+        let zeros;
+        let data = match data {
+            Some(data) => data,
+            None => {
+                zeros = vec![0; 4 * width as usize * height as usize];
+                &zeros
+            }
+        };
+        texture.upload(gl, WebGlRenderingContext::RGBA, (width, height), data)?;
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or_else(|| String::from("Unable to create framebuffer object"))?;
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            WebGlRenderingContext::FRAMEBUFFER,
+            WebGlRenderingContext::COLOR_ATTACHMENT0,
+            WebGlRenderingContext::TEXTURE_2D,
+            Some(&texture.texture),
+            0,
+        );
+        let status = gl.check_framebuffer_status(WebGlRenderingContext::FRAMEBUFFER);
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, None);
+        if status != WebGlRenderingContext::FRAMEBUFFER_COMPLETE {
+            return Err(format!("Incomplete framebuffer, status {:#x}", status).into());
+        }
+        Ok(Framebuffer {
+            framebuffer,
+            texture,
+            size: (width, height),
+        })
+    }
+
+    /// Renders into the texture until `unbind`, with a viewport covering all of it.
+    pub fn bind(&self, gl: &WebGlRenderingContext) {
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, self.size.0 as i32, self.size.1 as i32);
+    }
+
+    /// Renders into the canvas again.
+    pub fn unbind(gl: &WebGlRenderingContext) {
+        gl.bind_framebuffer(WebGlRenderingContext::FRAMEBUFFER, None);
+    }
+}
@@ -1,9 +1,13 @@
 pub mod attribute;
 pub mod enums;
+pub mod framebuffer;
 pub mod object;
 pub mod program;
+pub mod texture;
 
 pub use attribute::*;
 pub use enums::*;
+pub use framebuffer::*;
 pub use object::*;
 pub use program::*;
+pub use texture::*;
@@ -0,0 +1,94 @@
+use wasm_bindgen::JsValue;
+use web_sys::{WebGlRenderingContext, WebGlTexture};
+
+/// A 2D texture bound to its own texture unit, sampled without filtering so that texels stay sharp.
+pub struct Texture {
+    pub texture: WebGlTexture,
+    pub unit: u32,
+}
+
+impl Texture {
+    pub fn new(gl: &WebGlRenderingContext, unit: u32) -> Result<Texture, String> {
+        let texture = gl
+            .create_texture()
+            .ok_or_else(|| String::from("Unable to create texture object"))?;
+        let texture = Texture { texture, unit };
+        texture.bind(gl);
+        [
+            (
+                WebGlRenderingContext::TEXTURE_MIN_FILTER,
+                WebGlRenderingContext::NEAREST,
+            ),
+            (
+                WebGlRenderingContext::TEXTURE_MAG_FILTER,
+                WebGlRenderingContext::NEAREST,
+            ),
+            (
+                WebGlRenderingContext::TEXTURE_WRAP_S,
+                WebGlRenderingContext::CLAMP_TO_EDGE,
+            ),
+            (
+                WebGlRenderingContext::TEXTURE_WRAP_T,
+                WebGlRenderingContext::CLAMP_TO_EDGE,
+            ),
+        ]
+        .iter()
+        .for_each(|(name, value)| {
+            gl.tex_parameteri(WebGlRenderingContext::TEXTURE_2D, *name, *value as i32);
+        });
+        Ok(texture)
+    }
+
+    pub fn bind(&self, gl: &WebGlRenderingContext) {
+        gl.active_texture(WebGlRenderingContext::TEXTURE0 + self.unit);
+        gl.bind_texture(WebGlRenderingContext::TEXTURE_2D, Some(&self.texture));
+    }
+
+    /// Uploads `width * height` texels of the given format, row by row.
+    pub fn upload(
+        &self,
+        gl: &WebGlRenderingContext,
+        format: u32,
+        (width, height): (u32, u32),
+        data: &[u8],
+    ) -> Result<(), JsValue> {
+        self.bind(gl);
+        gl.pixel_storei(WebGlRenderingContext::UNPACK_ALIGNMENT, 1);
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            WebGlRenderingContext::TEXTURE_2D,
+            0,
+            format as i32,
+            width as i32,
+            height as i32,
+            0,
+            format,
+            WebGlRenderingContext::UNSIGNED_BYTE,
+            Some(data),
+        )
+    }
+
+    /// Uploads `width * height` texels of floats, which needs the `OES_texture_float` extension.
+    pub fn upload_f32(
+        &self,
+        gl: &WebGlRenderingContext,
+        format: u32,
+        (width, height): (u32, u32),
+        data: &[f32],
+    ) -> Result<(), JsValue> {
+        self.bind(gl);
+        gl.pixel_storei(WebGlRenderingContext::UNPACK_ALIGNMENT, 1);
+        // The view must not outlive `data`, nothing is allocated in wasm memory until the upload is done
+        let view = unsafe { js_sys::Float32Array::view(data) };
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+            WebGlRenderingContext::TEXTURE_2D,
+            0,
+            format as i32,
+            width as i32,
+            height as i32,
+            0,
+            format,
+            WebGlRenderingContext::FLOAT,
+            Some(&view),
+        )
+    }
+}
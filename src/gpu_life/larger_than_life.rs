@@ -0,0 +1,182 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_of_life::Random;
+use crate::gpu_life::{soup, GpuRule, MAX_RANGE};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The `(2r + 1)²` cells of the square around a cell.
+    Moore,
+    /// The cells at most `r` steps away along the axes, a diamond.
+    VonNeumann,
+}
+
+/// A Larger than Life rule, see https://conwaylife.com/wiki/Larger_than_Life
+///
+/// Like Generations, a live cell that does not survive goes through the dying states `2..states`,
+/// and only live cells are counted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LargerThanLife {
+    pub range: u32,
+    pub states: u8,
+    /// Whether a live cell counts itself.
+    pub middle: bool,
+    pub survival: (u32, u32),
+    pub birth: (u32, u32),
+    pub neighborhood: Neighborhood,
+}
+
+/// Bosco's rule.
+impl Default for LargerThanLife {
+    fn default() -> Self {
+        "R5,C2,M1,S34..58,B34..45,NM".parse().unwrap()
+    }
+}
+
+impl FromStr for LargerThanLife {
+    type Err = String;
+
+    /// Parses Golly's notation, like `R5,C2,M1,S34..58,B34..45,NM` for Bosco's rule.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid Larger than Life rule `{}`, expected `R5,C2,M1,S34..58,B34..45,NM`",
+                s
+            )
+        };
+        let parts: Vec<&str> = s.trim().split(',').map(str::trim).collect();
+        let part = |prefix: char| {
+            parts
+                .iter()
+                .find(|part| part.starts_with(prefix) || part.starts_with(prefix.to_ascii_lowercase()))
+                .map(|part| &part[1..])
+                .ok_or_else(invalid)
+        };
+        let number = |value: &str| value.parse::<u32>().map_err(|_| invalid());
+        let interval = |value: &str| {
+            let (min, max) = value.split_once("..").ok_or_else(invalid)?;
+            Ok::<_, String>((number(min)?, number(max)?))
+        };
+
+        let range = number(part('R')?)?;
+        if range == 0 || range > MAX_RANGE {
+            return Err(format!("the range of a rule is from 1 to {}, got {}", MAX_RANGE, range));
+        }
+        let states = match number(part('C').unwrap_or("2"))? {
+            // C0 and C1 are the same as C2 in Golly
+            0 | 1 => 2,
+            states if states <= 255 => states as u8,
+            states => return Err(format!("a rule has at most 255 states, got {}", states)),
+        };
+        let neighborhood = match part('N').unwrap_or("M") {
+            "M" | "m" => Neighborhood::Moore,
+            "N" | "n" => Neighborhood::VonNeumann,
+            _ => return Err(invalid()),
+        };
+        let middle = match part('M') {
+            Ok(middle) => number(middle)? != 0,
+            Err(_) => false,
+        };
+        Ok(LargerThanLife {
+            range,
+            states,
+            middle,
+            survival: interval(part('S')?)?,
+            birth: interval(part('B')?)?,
+            neighborhood,
+        })
+    }
+}
+
+impl fmt::Display for LargerThanLife {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.range,
+            self.states,
+            self.middle as u8,
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            match self.neighborhood {
+                Neighborhood::Moore => 'M',
+                Neighborhood::VonNeumann => 'N',
+            }
+        )
+    }
+}
+
+/// The state of a cell is in the red channel.
+impl GpuRule for LargerThanLife {
+    fn next_source(&self) -> String {
+        let skip_corners = match self.neighborhood {
+            Neighborhood::Moore => "",
+            Neighborhood::VonNeumann => "if (abs(float(dx)) + abs(float(dy)) > float(RANGE)) continue;",
+        };
+        let skip_middle = if self.middle {
+            ""
+        } else {
+            "if (dx == 0 && dy == 0) continue;"
+        };
+        format!(
+            r#"
+            const int RANGE = {range};
+            const float STATES = {states:?};
+
+            float state(vec2 offset) {{
+              return floor(texel(offset).r * 255.0 + 0.5);
+            }}
+
+            vec4 next() {{
+              float count = 0.0;
+              for (int dy = -RANGE; dy <= RANGE; dy++) {{
+                for (int dx = -RANGE; dx <= RANGE; dx++) {{
+                  {skip_corners}
+                  {skip_middle}
+                  if (state(vec2(dx, dy)) == 1.0) count += 1.0;
+                }}
+              }}
+              float current = state(vec2(0.0));
+              float result = 0.0;
+              if (current == 0.0) {{
+                result = count >= {birth_min:?} && count <= {birth_max:?} ? 1.0 : 0.0;
+              }} else if (current == 1.0) {{
+                result = count >= {survival_min:?} && count <= {survival_max:?} ? 1.0 : mod(2.0, STATES);
+              }} else {{
+                result = mod(current + 1.0, STATES);
+              }}
+              return vec4(result / 255.0, 0.0, 0.0, 1.0);
+            }}
+            "#,
+            range = self.range,
+            states = self.states as f32,
+            skip_corners = skip_corners,
+            skip_middle = skip_middle,
+            birth_min = self.birth.0 as f32,
+            birth_max = self.birth.1 as f32,
+            survival_min = self.survival.0 as f32,
+            survival_max = self.survival.1 as f32,
+        )
+    }
+
+    /// Live cells are the last shade, dying cells fade towards the first one.
+    fn shade_source(&self) -> String {
+        format!(
+            r#"
+            float shade(vec4 texel) {{
+              float state = floor(texel.r * 255.0 + 0.5);
+              return state == 0.0 ? 0.0 : ({states:?} - state) / ({states:?} - 1.0);
+            }}
+            "#,
+            states = self.states as f32
+        )
+    }
+
+    /// Cells are alive with a probability of one half.
+    fn seed(&self, random: &mut Random, size: (u32, u32)) -> Vec<u8> {
+        soup(random, size, |random| [(random.next_f64() < 0.5) as u8, 0, 0, 255])
+    }
+}
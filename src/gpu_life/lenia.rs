@@ -0,0 +1,283 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game_of_life::Random;
+use crate::gpu_life::{glsl_float, soup, GpuRule, MAX_RANGE};
+
+/// The shape of every ring of a kernel, from its inner edge at 0 to its outer edge at 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    Exponential,
+    Polynomial,
+    Step,
+}
+
+impl Kernel {
+    pub fn core(&self, r: f64) -> f64 {
+        match self {
+            Kernel::Exponential if r > 0.0 && r < 1.0 => (4.0 - 1.0 / (r * (1.0 - r))).exp(),
+            Kernel::Exponential => 0.0,
+            Kernel::Polynomial => (4.0 * r * (1.0 - r)).powi(4),
+            Kernel::Step => (0.25..=0.75).contains(&r) as u8 as f64,
+        }
+    }
+
+    /// The GLSL of `core`.
+    fn source(&self) -> &'static str {
+        match self {
+            Kernel::Exponential => "return r > 0.0 && r < 1.0 ? exp(4.0 - 1.0 / (r * (1.0 - r))) : 0.0;",
+            Kernel::Polynomial => "return pow(4.0 * r * (1.0 - r), 4.0);",
+            Kernel::Step => "return r >= 0.25 && r <= 0.75 ? 1.0 : 0.0;",
+        }
+    }
+}
+
+/// How fast the value of a cell grows, from -1 to 1, given the weighted sum of its neighbourhood.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Growth {
+    Gaussian,
+    Polynomial,
+    Step,
+}
+
+impl Growth {
+    /// The GLSL of the growth at `u`, with the constants `MU` and `SIGMA`.
+    fn source(&self) -> &'static str {
+        match self {
+            Growth::Gaussian => "return 2.0 * exp(-(u - MU) * (u - MU) / (2.0 * SIGMA * SIGMA)) - 1.0;",
+            Growth::Polynomial => {
+                "return 2.0 * pow(max(0.0, 1.0 - (u - MU) * (u - MU) / (9.0 * SIGMA * SIGMA)), 4.0) - 1.0;"
+            }
+            Growth::Step => "return abs(u - MU) <= SIGMA ? 1.0 : -1.0;",
+        }
+    }
+}
+
+/// The parameters of Lenia, a cellular automaton with continuous states, space and time, see
+/// https://arxiv.org/abs/1812.05433
+///
+/// The kernel is made of concentric rings, one for each peak, weighted by the peaks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lenia {
+    pub radius: u32,
+    pub peaks: Vec<f64>,
+    pub kernel: Kernel,
+    pub growth: Growth,
+    pub mu: f64,
+    pub sigma: f64,
+    /// The number of steps per unit of time.
+    pub time: f64,
+}
+
+/// Orbium, the glider of Lenia.
+impl Default for Lenia {
+    fn default() -> Self {
+        Lenia {
+            radius: 13,
+            peaks: vec![1.0],
+            kernel: Kernel::Exponential,
+            growth: Growth::Gaussian,
+            mu: 0.15,
+            sigma: 0.015,
+            time: 10.0,
+        }
+    }
+}
+
+impl FromStr for Lenia {
+    type Err = String;
+
+    /// Parses comma separated parameters like `R=13,T=10,m=0.15,s=0.015,b=1;0.5,k=exponential,g=gaussian`,
+    /// missing ones are those of Orbium.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lenia = Lenia::default();
+        for parameter in s.split(',').map(str::trim).filter(|parameter| !parameter.is_empty()) {
+            let (name, value) = parameter
+                .split_once('=')
+                .ok_or_else(|| format!("expected a parameter like `m=0.15`, got `{}`", parameter))?;
+            let number = |value: &str| {
+                value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(|| format!("invalid number `{}` for parameter `{}`", value, name))
+            };
+            match name {
+                "R" => {
+                    lenia.radius = value.parse().map_err(|_| format!("invalid radius `{}`", value))?;
+                    if lenia.radius == 0 || lenia.radius > MAX_RANGE {
+                        return Err(format!("the radius is from 1 to {}, got {}", MAX_RANGE, lenia.radius));
+                    }
+                }
+                "T" => lenia.time = number(value)?,
+                "m" => lenia.mu = number(value)?,
+                "s" => lenia.sigma = number(value)?,
+                "b" => lenia.peaks = value.split(';').map(number).collect::<Result<_, _>>()?,
+                "k" => {
+                    lenia.kernel = match value {
+                        "exponential" => Kernel::Exponential,
+                        "polynomial" => Kernel::Polynomial,
+                        "step" => Kernel::Step,
+                        _ => {
+                            return Err(format!(
+                                "unknown kernel `{}`, expected `exponential`, `polynomial` or `step`",
+                                value
+                            ))
+                        }
+                    }
+                }
+                "g" => {
+                    lenia.growth = match value {
+                        "gaussian" => Growth::Gaussian,
+                        "polynomial" => Growth::Polynomial,
+                        "step" => Growth::Step,
+                        _ => {
+                            return Err(format!(
+                                "unknown growth `{}`, expected `gaussian`, `polynomial` or `step`",
+                                value
+                            ))
+                        }
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "unknown parameter `{}`, expected `R`, `T`, `m`, `s`, `b`, `k` or `g`",
+                        name
+                    ))
+                }
+            }
+        }
+        if lenia.time <= 0.0 || lenia.sigma <= 0.0 {
+            return Err(String::from("the time and sigma of Lenia are positive"));
+        }
+        if lenia.kernel_sum() <= 0.0 {
+            return Err(format!("the kernel of `{}` is empty", lenia));
+        }
+        Ok(lenia)
+    }
+}
+
+impl fmt::Display for Lenia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let peaks: Vec<String> = self.peaks.iter().map(f64::to_string).collect();
+        write!(
+            f,
+            "R={},T={},m={},s={},b={},k={},g={}",
+            self.radius,
+            self.time,
+            self.mu,
+            self.sigma,
+            peaks.join(";"),
+            format!("{:?}", self.kernel).to_lowercase(),
+            format!("{:?}", self.growth).to_lowercase()
+        )
+    }
+}
+
+impl Lenia {
+    /// The weight of a cell at distance `r` from the center, `1` being the radius.
+    pub fn weight(&self, r: f64) -> f64 {
+        if r >= 1.0 {
+            return 0.0;
+        }
+        let ring = r * self.peaks.len() as f64;
+        self.peaks[ring as usize] * self.kernel.core(ring.fract())
+    }
+
+    /// The sum of the weights of the kernel, the growth depends on the average weighted by the kernel.
+    pub fn kernel_sum(&self) -> f64 {
+        let radius = self.radius as i64;
+        let mut sum = 0.0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                sum += self.weight(((dx * dx + dy * dy) as f64).sqrt() / radius as f64);
+            }
+        }
+        sum
+    }
+}
+
+/// The value of a cell is in the red channel, with 8 more bits of precision in the green channel.
+impl GpuRule for Lenia {
+    fn next_source(&self) -> String {
+        let peaks: String = self
+            .peaks
+            .iter()
+            .enumerate()
+            .map(|(i, peak)| {
+                format!(
+                    "if (ring < {}) return {};",
+                    glsl_float(i as f64 + 1.0),
+                    glsl_float(*peak)
+                )
+            })
+            .collect();
+        format!(
+            r#"
+            const int RADIUS = {radius};
+            const float PEAKS = {peak_count};
+            const float KERNEL_SUM = {kernel_sum};
+            const float MU = {mu};
+            const float SIGMA = {sigma};
+            const float DT = {dt};
+
+            float value(vec2 offset) {{
+              vec4 color = texel(offset);
+              return color.r + color.g / 255.0;
+            }}
+
+            float peak(float ring) {{
+              {peaks}
+              return 0.0;
+            }}
+
+            float core(float r) {{
+              {core}
+            }}
+
+            float growth(float u) {{
+              {growth}
+            }}
+
+            vec4 next() {{
+              float u = 0.0;
+              for (int dy = -RADIUS; dy <= RADIUS; dy++) {{
+                for (int dx = -RADIUS; dx <= RADIUS; dx++) {{
+                  float r = length(vec2(dx, dy)) / float(RADIUS);
+                  if (r < 1.0) {{
+                    float ring = r * PEAKS;
+                    u += peak(floor(ring)) * core(fract(ring)) * value(vec2(dx, dy));
+                  }}
+                }}
+              }}
+              float result = clamp(value(vec2(0.0)) + DT * growth(u / KERNEL_SUM), 0.0, 1.0);
+              return vec4(floor(result * 255.0) / 255.0, fract(result * 255.0), 0.0, 1.0);
+            }}
+            "#,
+            radius = self.radius,
+            peak_count = glsl_float(self.peaks.len() as f64),
+            kernel_sum = glsl_float(self.kernel_sum()),
+            mu = glsl_float(self.mu),
+            sigma = glsl_float(self.sigma),
+            dt = glsl_float(1.0 / self.time),
+            peaks = peaks,
+            core = self.kernel.source(),
+            growth = self.growth.source(),
+        )
+    }
+
+    fn shade_source(&self) -> String {
+        String::from(
+            r#"
+            float shade(vec4 texel) {
+              return texel.r + texel.g / 255.0;
+            }
+            "#,
+        )
+    }
+
+    /// Random values in the middle.
+    fn seed(&self, random: &mut Random, size: (u32, u32)) -> Vec<u8> {
+        soup(random, size, |random| [(random.next_f64() * 256.0) as u8, 0, 0, 255])
+    }
+}
@@ -0,0 +1,306 @@
+mod larger_than_life;
+mod lenia;
+
+pub use larger_than_life::*;
+pub use lenia::*;
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsValue;
+use web_sys::WebGlRenderingContext;
+
+use crate::dom;
+use crate::game_of_life::{ColorScheme, Random};
+use crate::gl::{Attribute, AttributeType, Dimension, Framebuffer, Program, ProgramDescription, Texture, UniformValue};
+
+/// The largest range of a neighbourhood, every step reads `(2 * MAX_RANGE + 1)²` texels per cell.
+pub const MAX_RANGE: u32 = 32;
+
+/// The rule of an automaton run by a fragment shader, one texel per cell.
+pub trait GpuRule: fmt::Display {
+    /// The GLSL of `vec4 next()`, the next texel of the cell being drawn. The texel of a cell at an offset
+    /// from it is `texel(vec2 offset)`, the grid being a torus.
+    fn next_source(&self) -> String;
+
+    /// The GLSL of `float shade(vec4 texel)`, from 0 for dead cells to 1.
+    fn shade_source(&self) -> String;
+
+    /// The RGBA texels of a random grid.
+    fn seed(&self, random: &mut Random, size: (u32, u32)) -> Vec<u8>;
+}
+
+/// A float literal, GLSL needs a decimal point or an exponent.
+pub fn glsl_float(x: f64) -> String {
+    format!("{:?}", x)
+}
+
+/// The RGBA texels of a grid with random texels in the middle half of each side and zeros around.
+pub fn soup<F>(random: &mut Random, (width, height): (u32, u32), mut texel: F) -> Vec<u8>
+where
+    F: FnMut(&mut Random) -> [u8; 4],
+{
+    let mut texels = vec![0; 4 * width as usize * height as usize];
+    for row in height / 4..height - height / 4 {
+        for column in width / 4..width - width / 4 {
+            let i = 4 * (row as usize * width as usize + column as usize);
+            texels[i..i + 4].copy_from_slice(&texel(random));
+        }
+    }
+    texels
+}
+
+const VERTEX_SOURCE: &str = r#"
+    precision highp float;
+    attribute vec2 a_position;
+    void main() {
+      gl_Position = vec4(a_position, 0.0, 1.0);
+    }
+"#;
+
+/// A triangle covering the viewport.
+fn full_screen() -> Vec<Attribute> {
+    vec![Attribute {
+        name: "a_position",
+        attribute_type: AttributeType::Vector(Dimension::D2),
+        vertices: vec![-1.0, -1.0, 3.0, -1.0, -1.0, 3.0],
+    }]
+}
+
+/// Runs a `GpuRule` on a torus, the cells in one of two textures: every step renders the next generation
+/// from one into the other, then they swap.
+struct Simulation {
+    rule: Box<dyn GpuRule>,
+    step_program: Program,
+    display_program: Program,
+    grids: [Framebuffer; 2],
+    /// The grid with the current generation.
+    current: usize,
+    palette: Texture,
+    size: (u32, u32),
+    random: Random,
+    steps_per_frame: u32,
+    paused: bool,
+    generation: u64,
+}
+
+impl Simulation {
+    fn new(rule: Box<dyn GpuRule>, size: (u32, u32), scheme: &ColorScheme) -> Result<Simulation, JsValue> {
+        let step_source = format!(
+            r#"
+            precision highp float;
+            uniform sampler2D u_grid;
+            uniform vec2 u_size;
+
+            vec4 texel(vec2 offset) {{
+              return texture2D(u_grid, fract((gl_FragCoord.xy + offset) / u_size));
+            }}
+            {}
+            void main() {{
+              gl_FragColor = next();
+            }}
+            "#,
+            rule.next_source()
+        );
+        let display_source = format!(
+            r#"
+            precision highp float;
+            uniform sampler2D u_grid;
+            uniform sampler2D u_palette;
+            uniform vec2 u_dimension;
+            uniform vec2 u_size;
+            uniform float u_scale;
+            {}
+            void main() {{
+              vec2 cell = (gl_FragCoord.xy - 0.5 * u_dimension) / u_scale + 0.5 * u_size;
+              if (cell.x < 0.0 || cell.y < 0.0 || cell.x >= u_size.x || cell.y >= u_size.y) discard;
+              float index = floor(clamp(shade(texture2D(u_grid, cell / u_size)), 0.0, 1.0) * 255.0 + 0.5);
+              gl_FragColor = vec4(texture2D(u_palette, vec2((index + 0.5) / 256.0, 0.5)).rgb, 1.0);
+            }}
+            "#,
+            rule.shade_source()
+        );
+        let step_program = Program::new(
+            "canvas",
+            ProgramDescription {
+                vertex_source: VERTEX_SOURCE,
+                fragment_source: &step_source,
+                attributes: full_screen(),
+                ..Default::default()
+            },
+        )?;
+        let display_program = Program::new(
+            "canvas",
+            ProgramDescription {
+                vertex_source: VERTEX_SOURCE,
+                fragment_source: &display_source,
+                attributes: full_screen(),
+                ..Default::default()
+            },
+        )?;
+
+        let gl = &step_program.gl;
+        let mut random = Random::new(js_sys::Date::now() as u64);
+        let seed = rule.seed(&mut random, size);
+        let grids = [
+            Framebuffer::new(gl, 0, size, Some(&seed))?,
+            Framebuffer::new(gl, 1, size, None)?,
+        ];
+        let palette = Texture::new(gl, 2)?;
+        let colors: Vec<u8> = scheme.palette().concat();
+        palette.upload(gl, WebGlRenderingContext::RGB, (256, 1), &colors)?;
+
+        Ok(Simulation {
+            rule,
+            step_program,
+            display_program,
+            grids,
+            current: 0,
+            palette,
+            size,
+            random,
+            steps_per_frame: 1,
+            paused: false,
+            generation: 0,
+        })
+    }
+
+    /// Starts again from a new random grid.
+    fn reseed(&mut self) -> Result<(), JsValue> {
+        let seed = self.rule.seed(&mut self.random, self.size);
+        let grid = &self.grids[self.current].texture;
+        grid.upload(&self.step_program.gl, WebGlRenderingContext::RGBA, self.size, &seed)?;
+        self.generation = 0;
+        Ok(())
+    }
+
+    fn step(&mut self) {
+        let program = &self.step_program;
+        let (from, to) = (&self.grids[self.current], &self.grids[1 - self.current]);
+        to.bind(&program.gl);
+        program.prepare_render();
+        program.set_uniform("u_grid", UniformValue::Int(from.texture.unit as i32));
+        program.set_uniform(
+            "u_size",
+            UniformValue::Vector2([self.size.0 as f32, self.size.1 as f32]),
+        );
+        program.render();
+        Framebuffer::unbind(&program.gl);
+        self.current = 1 - self.current;
+        self.generation += 1;
+    }
+
+    fn render(&self) {
+        let program = &self.display_program;
+        let (width, height) = (
+            program.gl.drawing_buffer_width() as f32,
+            program.gl.drawing_buffer_height() as f32,
+        );
+        let scale = (width / self.size.0 as f32).min(height / self.size.1 as f32);
+        Program::clear_gl(&program.gl);
+        program.prepare_render();
+        program.set_uniform(
+            "u_grid",
+            UniformValue::Int(self.grids[self.current].texture.unit as i32),
+        );
+        program.set_uniform("u_palette", UniformValue::Int(self.palette.unit as i32));
+        program.set_uniform("u_dimension", UniformValue::Vector2([width, height]));
+        program.set_uniform(
+            "u_size",
+            UniformValue::Vector2([self.size.0 as f32, self.size.1 as f32]),
+        );
+        program.set_uniform("u_scale", UniformValue::Float(scale));
+        program.render();
+    }
+}
+
+/// A running GPU automaton.
+#[wasm_bindgen]
+pub struct GpuLife {
+    simulation: Rc<RefCell<Simulation>>,
+}
+
+#[wasm_bindgen]
+impl GpuLife {
+    pub fn rule(&self) -> String {
+        self.simulation.borrow().rule.to_string()
+    }
+
+    pub fn generation(&self) -> f64 {
+        self.simulation.borrow().generation as f64
+    }
+
+    pub fn set_steps_per_frame(&self, steps: u32) {
+        self.simulation.borrow_mut().steps_per_frame = steps.max(1);
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.simulation.borrow_mut().paused = paused;
+    }
+
+    pub fn reseed(&self) -> Result<(), JsValue> {
+        self.simulation.borrow_mut().reseed()
+    }
+}
+
+/// Space pauses, `.` steps once, `r` reseeds, `+` and `-` change the number of steps per frame.
+fn start(
+    rule: Box<dyn GpuRule>,
+    size: Option<u32>,
+    scheme: Option<String>,
+    default_scheme: &str,
+) -> Result<GpuLife, JsValue> {
+    let size = size.unwrap_or(256).max(1);
+    let scheme: ColorScheme = scheme.as_deref().unwrap_or(default_scheme).parse()?;
+    let simulation = Rc::new(RefCell::new(Simulation::new(rule, (size, size), &scheme)?));
+
+    {
+        let simulation = simulation.clone();
+        dom::add_keyboard_event_listener("keydown", move |e| {
+            let mut simulation = simulation.borrow_mut();
+            match e.key().as_str() {
+                " " => simulation.paused = !simulation.paused,
+                "." => simulation.step(),
+                "r" => {
+                    if let Err(error) = simulation.reseed() {
+                        web_sys::console::error_1(&error);
+                    }
+                }
+                "+" => simulation.steps_per_frame = (simulation.steps_per_frame * 2).min(64),
+                "-" => simulation.steps_per_frame = (simulation.steps_per_frame / 2).max(1),
+                _ => {}
+            }
+        });
+    }
+
+    {
+        let simulation = simulation.clone();
+        dom::request_animation_frame(move |_t, _dt| {
+            let mut simulation = simulation.borrow_mut();
+            if !simulation.paused {
+                (0..simulation.steps_per_frame).for_each(|_| simulation.step());
+            }
+            simulation.render();
+        });
+    }
+
+    Ok(GpuLife { simulation })
+}
+
+/// Runs a Larger than Life rule in Golly's notation, Bosco's rule by default, on a `size` by `size` torus.
+#[wasm_bindgen]
+pub fn larger_than_life(rule: Option<String>, size: Option<u32>, scheme: Option<String>) -> Result<GpuLife, JsValue> {
+    let rule = match rule {
+        Some(rule) => rule.parse()?,
+        None => LargerThanLife::default(),
+    };
+    start(Box::new(rule), size, scheme, "classic")
+}
+
+/// Runs Lenia with comma separated parameters, Orbium by default, on a `size` by `size` torus.
+#[wasm_bindgen]
+pub fn lenia(parameters: Option<String>, size: Option<u32>, scheme: Option<String>) -> Result<GpuLife, JsValue> {
+    let lenia = parameters.as_deref().unwrap_or("").parse::<Lenia>()?;
+    start(Box::new(lenia), size, scheme, "ocean")
+}
@@ -1,11 +1,17 @@
+mod color;
 mod dom;
 mod geometry;
 mod gl;
 
+mod elementary;
 mod game_of_life;
+mod gpu_life;
 mod mendelbrot;
 mod threed;
 mod tracer;
+mod turmite;
+
+pub use game_of_life::{JsCensus, JsUniverse};
 
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
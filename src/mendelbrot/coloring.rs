@@ -0,0 +1,132 @@
+use std::str::FromStr;
+
+use crate::mendelbrot::PALETTE_SIZE;
+
+/// The number of bins of the histogram of iterations.
+pub const HISTOGRAM_BINS: u32 = 1024;
+
+/// How escaped points are mapped to the palette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Coloring {
+    /// By the number of iterations, in bands.
+    Iterations,
+    /// By the normalized iteration count, which is continuous across bands.
+    Smooth,
+    /// By the fraction of the pixels that escaped sooner, so that every color covers as many pixels.
+    Histogram,
+}
+
+impl FromStr for Coloring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "iterations" => Ok(Coloring::Iterations),
+            "smooth" => Ok(Coloring::Smooth),
+            "histogram" => Ok(Coloring::Histogram),
+            _ => Err(format!(
+                "unknown coloring `{}`, expected `iterations`, `smooth` or `histogram`",
+                s
+            )),
+        }
+    }
+}
+
+impl Coloring {
+    pub fn next(self) -> Coloring {
+        match self {
+            Coloring::Iterations => Coloring::Smooth,
+            Coloring::Smooth => Coloring::Histogram,
+            Coloring::Histogram => Coloring::Iterations,
+        }
+    }
+}
+
+/// Iteration shaders do not choose colors, they write `encode(value, flag)`: the iteration count, with
+/// 8 bits of fraction in the red, green and blue bytes, and a flag in the alpha byte. The flag is 255 for
+/// escaped points, the root plus one for points that converged, and 0 with a zero count for points that
+/// did neither.
+pub const ENCODE_SOURCE: &str = r#"
+        vec4 encode(float value, float flag) {
+          float bits = floor(clamp(value, 0.0, 65535.0) * 256.0);
+          return vec4(mod(bits, 256.0), mod(floor(bits / 256.0), 256.0), floor(bits / 65536.0), flag) / 255.0;
+        }
+"#;
+
+/// Colors the encoded iterations of every pixel with the palette, offset by the palette cycling.
+pub fn coloring_source() -> String {
+    format!(
+        r#"
+        precision highp float;
+
+        uniform vec2 u_size;
+        uniform sampler2D u_iterations;
+        uniform sampler2D u_palette;
+        uniform sampler2D u_histogram;
+        uniform int u_coloring;
+        uniform float u_max_iterations;
+        uniform float u_roots;
+        uniform float u_offset;
+
+        const float PALETTE_SIZE = {palette_size:?};
+        const float HISTOGRAM_BINS = {histogram_bins:?};
+
+        vec3 palette(float t) {{
+          return texture2D(u_palette, vec2((floor(fract(t + u_offset) * PALETTE_SIZE) + 0.5) / PALETTE_SIZE, 0.5)).rgb;
+        }}
+
+        // The fraction of the pixels with fewer iterations, interpolated within a bin
+        float rank(float value) {{
+          float bin = clamp(value / u_max_iterations, 0.0, 1.0) * (HISTOGRAM_BINS - 1.0);
+          float i = floor(bin);
+          vec2 end = texture2D(u_histogram, vec2((i + 0.5) / HISTOGRAM_BINS, 0.5)).rg;
+          vec2 start = i == 0.0 ? vec2(0.0) : texture2D(u_histogram, vec2((i - 0.5) / HISTOGRAM_BINS, 0.5)).rg;
+          return mix(dot(start, vec2(256.0, 1.0)), dot(end, vec2(256.0, 1.0)), bin - i) * 255.0 / 65535.0;
+        }}
+
+        void main() {{
+          vec4 texel = floor(texture2D(u_iterations, gl_FragCoord.xy / u_size) * 255.0 + 0.5);
+          float value = dot(texel.rgb, vec3(1.0, 256.0, 65536.0)) / 256.0;
+          vec3 color;
+          if (texel.a == 0.0) {{
+            color = vec3(0.85, 0.99, 1.0);
+          }} else if (texel.a < 255.0) {{
+            // Newton's method, a color for every root darkened by the iterations to reach it
+            color = palette((texel.a - 1.0) / u_roots) * (0.3 + 0.7 * exp(-0.05 * value));
+          }} else if (u_coloring == 0) {{
+            color = palette(floor(value) / u_max_iterations);
+          }} else if (u_coloring == 1) {{
+            color = palette(value / u_max_iterations);
+          }} else {{
+            color = palette(rank(value));
+          }}
+          gl_FragColor = vec4(color, 1.0);
+        }}
+        "#,
+        palette_size = PALETTE_SIZE as f32,
+        histogram_bins = HISTOGRAM_BINS as f32,
+    )
+}
+
+/// The cumulative histogram of the escaped pixels among encoded RGBA pixels, over `HISTOGRAM_BINS` bins
+/// from 0 to `max_iterations`. Every bin is an RGBA texel with the fraction of pixels up to its end in
+/// 16 bits, the high byte in red and the low byte in green.
+pub fn histogram_texels(pixels: &[u8], max_iterations: i32) -> Vec<u8> {
+    let mut counts = vec![0u32; HISTOGRAM_BINS as usize];
+    let scale = (HISTOGRAM_BINS - 1) as f64 / max_iterations.max(1) as f64;
+    for pixel in pixels.chunks(4).filter(|pixel| pixel[3] == 255) {
+        let value = (pixel[0] as u32 | (pixel[1] as u32) << 8 | (pixel[2] as u32) << 16) as f64 / 256.0;
+        let bin = ((value * scale) as usize).min(HISTOGRAM_BINS as usize - 1);
+        counts[bin] += 1;
+    }
+    let total = counts.iter().sum::<u32>().max(1) as f64;
+    let mut sum = 0;
+    counts
+        .iter()
+        .flat_map(|count| {
+            sum += count;
+            let fraction = (sum as f64 / total * 65535.0).round() as u16;
+            [(fraction >> 8) as u8, fraction as u8, 0, 255]
+        })
+        .collect()
+}
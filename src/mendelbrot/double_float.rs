@@ -0,0 +1,88 @@
+use crate::mendelbrot::ESCAPE_RADIUS;
+
+/// `x` as the sum of two floats, the second one holding the bits the first one cannot.
+pub fn split(x: f64) -> [f32; 2] {
+    let high = x as f32;
+    [high, (x - high as f64) as f32]
+}
+
+/// Iterates in double-float arithmetic, every number is the unevaluated sum of two floats, see
+/// https://andrewthall.org/papers/df64_qf128.pdf
+///
+/// The error terms are multiplied by `u_one`, which is always 1, so that compilers cannot simplify them away.
+pub fn fragment_source(encode: &str) -> String {
+    format!(
+        r#"
+        precision highp float;
+
+        uniform vec2 u_origin;
+        uniform vec2 u_dimension;
+        uniform vec2 u_zoom_center_x;
+        uniform vec2 u_zoom_center_y;
+        uniform float u_zoom_size;
+        uniform int u_max_iterations;
+        uniform float u_one;
+        uniform int u_julia;
+        uniform vec2 u_julia_c;
+
+        const float BAILOUT = {bailout:?};
+        {encode}
+
+        vec2 df_add(vec2 a, vec2 b) {{
+          float s = a.x + b.x;
+          float v = s * u_one - a.x;
+          float e = (a.x - (s - v)) + (b.x - v) + a.y + b.y;
+          float high = s + e;
+          return vec2(high, e - (high * u_one - s));
+        }}
+
+        vec2 df_mul(vec2 a, vec2 b) {{
+          float split_a = a.x * 4097.0;
+          float a1 = split_a * u_one - (split_a - a.x);
+          float a2 = a.x - a1;
+          float split_b = b.x * 4097.0;
+          float b1 = split_b * u_one - (split_b - b.x);
+          float b2 = b.x - b1;
+          float p = a.x * b.x;
+          float e = ((a1 * b1 - p) + a1 * b2 + a2 * b1) + a2 * b2 + (a.x * b.y + a.y * b.x);
+          float high = p + e;
+          return vec2(high, e - (high * u_one - p));
+        }}
+
+        void main() {{
+          vec2 uv = (gl_FragCoord.xy - u_origin) / u_dimension;
+          vec2 offset = (uv - vec2(0.5)) * u_zoom_size;
+          vec2 cx = df_add(u_zoom_center_x, vec2(offset.x, 0.0));
+          vec2 cy = df_add(u_zoom_center_y, vec2(offset.y, 0.0));
+          vec2 x = vec2(0.0);
+          vec2 y = vec2(0.0);
+          if (u_julia == 1) {{
+            x = cx;
+            y = cy;
+            cx = vec2(u_julia_c.x, 0.0);
+            cy = vec2(u_julia_c.y, 0.0);
+          }}
+          bool escaped = false;
+          int iterations = 0;
+          float size = 0.0;
+          for (int i = 0; i < 10000; i++) {{
+            if (i > u_max_iterations) break;
+            iterations = i;
+            vec2 xx = df_mul(x, x);
+            vec2 yy = df_mul(y, y);
+            vec2 xy = df_mul(x, y);
+            x = df_add(df_add(xx, -yy), cx);
+            y = df_add(df_add(xy, xy), cy);
+            size = x.x * x.x + y.x * y.x;
+            if (size > BAILOUT) {{
+              escaped = true;
+              break;
+            }}
+          }}
+          gl_FragColor = escaped ? encode(float(iterations) + 1.0 - log2(0.5 * log(size)), 255.0) : vec4(0.0);
+        }}
+        "#,
+        bailout = (ESCAPE_RADIUS * ESCAPE_RADIUS) as f32,
+        encode = encode,
+    )
+}
@@ -0,0 +1,261 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A signed fixed-point number of any precision, in two's complement.
+///
+/// The limbs are little endian: the last one is the integer part and the others are the fraction,
+/// 32 bits each. Operands of different precisions are extended to the larger one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fixed {
+    limbs: Vec<u32>,
+}
+
+impl Fixed {
+    pub fn zero(fraction_limbs: usize) -> Fixed {
+        Fixed {
+            limbs: vec![0; fraction_limbs + 1],
+        }
+    }
+
+    /// `x` rounded towards zero, it must be smaller than 2³¹ in magnitude.
+    pub fn from_f64(x: f64, fraction_limbs: usize) -> Fixed {
+        let mut fixed = Fixed::zero(fraction_limbs);
+        if x == 0.0 || !x.is_finite() {
+            return fixed;
+        }
+        let bits = x.abs().to_bits();
+        let (exponent, fraction) = ((bits >> 52) as i64, bits & ((1 << 52) - 1));
+        let (mantissa, exponent) = match exponent {
+            0 => (fraction, -1074),
+            exponent => (fraction | 1 << 52, exponent - 1075),
+        };
+        // Bit `i` of the limbs is worth 2^(i - 32 * fraction_limbs)
+        let shift = exponent + 32 * fraction_limbs as i64;
+        let bits = 32 * fixed.limbs.len() as i64;
+        for bit in (0..53).filter(|bit| mantissa >> bit & 1 == 1) {
+            let position = shift + bit;
+            if position >= 0 && position < bits {
+                fixed.limbs[position as usize / 32] |= 1 << (position % 32);
+            }
+        }
+        if x < 0.0 {
+            -&fixed
+        } else {
+            fixed
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        if self.is_negative() {
+            return -(-self).to_f64();
+        }
+        let fraction_limbs = self.fraction_limbs() as i32;
+        self.limbs
+            .iter()
+            .enumerate()
+            .map(|(i, limb)| *limb as f64 * pow2(32 * (i as i32 - fraction_limbs)))
+            .sum()
+    }
+
+    pub fn fraction_limbs(&self) -> usize {
+        self.limbs.len() - 1
+    }
+
+    /// The same number with more fraction bits, or truncated to fewer.
+    pub fn with_fraction_limbs(&self, fraction_limbs: usize) -> Fixed {
+        let current = self.fraction_limbs();
+        let limbs = if fraction_limbs >= current {
+            let mut limbs = vec![0; fraction_limbs - current];
+            limbs.extend_from_slice(&self.limbs);
+            limbs
+        } else {
+            self.limbs[current - fraction_limbs..].to_vec()
+        };
+        Fixed { limbs }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.limbs.last().unwrap() >> 31 == 1
+    }
+
+    fn abs(&self) -> Fixed {
+        if self.is_negative() {
+            -self
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Both numbers with the precision of the most precise one.
+    fn align(&self, other: &Fixed) -> (Fixed, Fixed) {
+        let fraction_limbs = self.fraction_limbs().max(other.fraction_limbs());
+        (
+            self.with_fraction_limbs(fraction_limbs),
+            other.with_fraction_limbs(fraction_limbs),
+        )
+    }
+}
+
+/// `2^exponent`, also for the subnormal powers that `powi` flushes to 0, as it divides by `2^-exponent`.
+fn pow2(exponent: i32) -> f64 {
+    2f64.powi(exponent.max(-1022)) * 2f64.powi((exponent + 1022).min(0))
+}
+
+impl Neg for &Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        let mut carry = 1;
+        let limbs = self
+            .limbs
+            .iter()
+            .map(|limb| {
+                let sum = !limb as u64 + carry;
+                carry = sum >> 32;
+                sum as u32
+            })
+            .collect();
+        Fixed { limbs }
+    }
+}
+
+impl Add for &Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: &Fixed) -> Fixed {
+        let (a, b) = self.align(other);
+        let mut carry = 0;
+        let limbs = a
+            .limbs
+            .iter()
+            .zip(b.limbs.iter())
+            .map(|(a, b)| {
+                let sum = *a as u64 + *b as u64 + carry;
+                carry = sum >> 32;
+                sum as u32
+            })
+            .collect();
+        Fixed { limbs }
+    }
+}
+
+impl Sub for &Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: &Fixed) -> Fixed {
+        self + &-other
+    }
+}
+
+/// Truncates the product to the precision of the operands.
+impl Mul for &Fixed {
+    type Output = Fixed;
+
+    fn mul(self, other: &Fixed) -> Fixed {
+        let negative = self.is_negative() != other.is_negative();
+        let (a, b) = self.align(other);
+        let (a, b) = (a.abs(), b.abs());
+        let n = a.limbs.len();
+        let mut product = vec![0u64; 2 * n];
+        for (i, a) in a.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, b) in b.limbs.iter().enumerate() {
+                let sum = product[i + j] + *a as u64 * *b as u64 + carry;
+                product[i + j] = sum & 0xffff_ffff;
+                carry = sum >> 32;
+            }
+            product[i + n] = carry;
+        }
+        let product = Fixed {
+            limbs: product[n - 1..2 * n - 1].iter().map(|limb| *limb as u32).collect(),
+        };
+        if negative {
+            -&product
+        } else {
+            product
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mendelbrot::Fixed;
+
+    const VALUES: [f64; 8] = [0.0, 1.0, -1.0, 0.5, -2.75, std::f64::consts::PI, -1e-9, 12345.678];
+
+    #[test]
+    fn round_trips() {
+        for x in VALUES {
+            assert_eq!(Fixed::from_f64(x, 3).to_f64(), x);
+        }
+    }
+
+    #[test]
+    fn negates() {
+        for x in VALUES {
+            assert_eq!((-&Fixed::from_f64(x, 3)).to_f64(), -x);
+        }
+        assert_eq!(-&Fixed::zero(3), Fixed::zero(3));
+    }
+
+    #[test]
+    fn adds_and_subtracts() {
+        for a in VALUES {
+            for b in VALUES {
+                let (x, y) = (Fixed::from_f64(a, 3), Fixed::from_f64(b, 3));
+                assert_eq!((&x + &y).to_f64(), a + b, "{} + {}", a, b);
+                assert_eq!((&x - &y).to_f64(), a - b, "{} - {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn multiplies_like_f64() {
+        for a in VALUES {
+            for b in VALUES {
+                let product = (&Fixed::from_f64(a, 3) * &Fixed::from_f64(b, 3)).to_f64();
+                assert!(
+                    (product - a * b).abs() <= 1e-15 * (a * b).abs().max(1.0),
+                    "{} * {}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncates_products() {
+        // 2^-32 squared is below the precision of one fraction limb
+        let tiny = Fixed::from_f64(2f64.powi(-32), 1);
+        assert_eq!(&tiny * &tiny, Fixed::zero(1));
+        assert_eq!((&tiny * &Fixed::from_f64(-3.0, 1)).to_f64(), -3.0 * 2f64.powi(-32));
+    }
+
+    #[test]
+    fn keeps_the_bits_of_subnormals_in_range() {
+        let smallest = f64::from_bits(1);
+        assert_eq!(Fixed::from_f64(smallest, 2), Fixed::zero(2));
+        assert_ne!(Fixed::from_f64(smallest, 34), Fixed::zero(34));
+        let subnormal = f64::from_bits(1 << 24);
+        assert_eq!(Fixed::from_f64(subnormal, 34).to_f64(), subnormal);
+        assert_eq!(Fixed::from_f64(-subnormal, 34).to_f64(), -subnormal);
+    }
+
+    #[test]
+    fn changes_precision() {
+        let x = Fixed::from_f64(-1.0 - 2f64.powi(-40), 2);
+        assert_eq!(x.with_fraction_limbs(4).fraction_limbs(), 4);
+        assert_eq!(x.with_fraction_limbs(4).to_f64(), x.to_f64());
+        // Truncating two's complement rounds towards minus infinity
+        assert_eq!(x.with_fraction_limbs(1).to_f64(), -1.0 - 2f64.powi(-32));
+    }
+
+    #[test]
+    fn extends_operands_to_the_larger_precision() {
+        let (a, b) = (Fixed::from_f64(1.0, 1), Fixed::from_f64(2f64.powi(-60), 2));
+        let sum = &a + &b;
+        assert_eq!(sum.fraction_limbs(), 2);
+        assert_eq!(sum.to_f64(), 1.0 + 2f64.powi(-60));
+        assert_eq!((&b * &a).fraction_limbs(), 2);
+    }
+}
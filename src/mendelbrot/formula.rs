@@ -0,0 +1,336 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::mendelbrot::julia::parse_numbers;
+use crate::mendelbrot::ESCAPE_RADIUS;
+
+/// Complex arithmetic for the iteration functions.
+const COMPLEX_SOURCE: &str = r#"
+        vec2 cmul(vec2 a, vec2 b) {
+          return vec2(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+        }
+
+        vec2 cdiv(vec2 a, vec2 b) {
+          return vec2(a.x * b.x + a.y * b.y, a.y * b.x - a.x * b.y) / dot(b, b);
+        }
+
+        vec2 cpow(vec2 a, float n) {
+          if (a.x == 0.0 && a.y == 0.0) return vec2(0.0);
+          float angle = n * atan(a.y, a.x);
+          return pow(length(a), n) * vec2(cos(angle), sin(angle));
+        }
+"#;
+
+/// The iteration function `f(x, c)` of a fractal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Formula {
+    /// `z^n + c`, the Mandelbrot set is the power 2.
+    Power(f64),
+    /// `(|Re z| + i|Im z|)^2 + c`.
+    BurningShip,
+    /// `conj(z)^2 + c`, also called the Mandelbar set.
+    Tricorn,
+    /// `z^2 + c + p * z'`, where `z'` is the previous `z`.
+    Phoenix { p: (f64, f64) },
+    /// Newton's method on a polynomial with real coefficients, highest degree first. Every starting point is
+    /// colored by the root it converges to, `c` is not used.
+    Newton(Vec<f64>),
+}
+
+impl Default for Formula {
+    fn default() -> Self {
+        Formula::Power(2.0)
+    }
+}
+
+impl FromStr for Formula {
+    type Err = String;
+
+    /// `z^n`, `burning-ship`, `tricorn`, `phoenix` or `phoenix:px,py`, or `newton:` and the coefficients of
+    /// a polynomial like `newton:1,0,0,-1` for `z^3 - 1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, parameters) = match s.trim().split_once(':') {
+            Some((name, parameters)) => (name, Some(parameters)),
+            None => (s.trim(), None),
+        };
+        match (name, parameters) {
+            ("burning-ship", None) => Ok(Formula::BurningShip),
+            ("tricorn", None) | ("mandelbar", None) => Ok(Formula::Tricorn),
+            ("phoenix", None) => Ok(Formula::Phoenix { p: (-0.5, 0.0) }),
+            ("phoenix", Some(p)) => match parse_numbers(p)?.as_slice() {
+                [x, y] => Ok(Formula::Phoenix { p: (*x, *y) }),
+                _ => Err(format!("expected a phoenix formula like `phoenix:-0.5,0`, got `{}`", s)),
+            },
+            ("newton", Some(coefficients)) => {
+                let coefficients: Vec<f64> = parse_numbers(coefficients)?
+                    .into_iter()
+                    .skip_while(|coefficient| *coefficient == 0.0)
+                    .collect();
+                if coefficients.len() < 3 {
+                    return Err(format!(
+                        "Newton's method needs a polynomial of degree 2 or more, got `{}`",
+                        s
+                    ));
+                }
+                Ok(Formula::Newton(coefficients))
+            }
+            (power, None) if power.starts_with("z^") => match power[2..].parse::<f64>() {
+                Ok(n) if n > 1.0 && n <= 64.0 => Ok(Formula::Power(n)),
+                _ => Err(format!("expected a power above 1 and up to 64, got `{}`", s)),
+            },
+            _ => Err(format!(
+                "unknown formula `{}`, expected `z^n`, `burning-ship`, `tricorn`, `phoenix` or `newton:...`",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Formula::Power(n) => write!(f, "z^{}", n),
+            Formula::BurningShip => write!(f, "burning-ship"),
+            Formula::Tricorn => write!(f, "tricorn"),
+            Formula::Phoenix { p } => write!(f, "phoenix:{},{}", p.0, p.1),
+            Formula::Newton(coefficients) => {
+                let coefficients: Vec<String> = coefficients.iter().map(|c| c.to_string()).collect();
+                write!(f, "newton:{}", coefficients.join(","))
+            }
+        }
+    }
+}
+
+impl Formula {
+    /// The formulas the `f` key goes through.
+    pub fn presets() -> Vec<Formula> {
+        vec![
+            Formula::Power(2.0),
+            Formula::Power(3.0),
+            Formula::Power(2.5),
+            Formula::BurningShip,
+            Formula::Tricorn,
+            Formula::Phoenix { p: (-0.5, 0.0) },
+            Formula::Newton(vec![1.0, 0.0, 0.0, -1.0]),
+            Formula::Newton(vec![1.0, 0.0, -2.0, 2.0]),
+        ]
+    }
+
+    /// The preset after this formula, or the first one.
+    pub fn next(&self) -> Formula {
+        let presets = Formula::presets();
+        let index = presets.iter().position(|formula| formula == self);
+        presets[index.map_or(0, |index| (index + 1) % presets.len())].clone()
+    }
+
+    /// Whether this is `z^2 + c`, the only formula the double-float and perturbation shaders iterate.
+    pub fn is_quadratic(&self) -> bool {
+        *self == Formula::Power(2.0)
+    }
+
+    /// The GLSL of the iteration, to splice in a fragment shader:
+    ///
+    /// - `vec2 start(vec2 point)`, the first `x` of the Mandelbrot set of the formula at `c = point`;
+    /// - `vec2 f(vec2 x, vec2 previous, vec2 c)`, the next `x` from the last two;
+    /// - `bool stop(vec2 x, vec2 previous)`, whether the point escaped or converged;
+    /// - `vec4 result(vec2 x, int iterations)`, the encoded iterations of a point that stopped.
+    ///
+    /// `ENCODE_SOURCE` must be defined before it.
+    pub fn source(&self) -> String {
+        let (start, next, stop, result) = match self {
+            Formula::Newton(coefficients) => (
+                "point".to_string(),
+                newton_source(coefficients),
+                "distance(x, previous) < 1e-5".to_string(),
+                root_source(&roots(coefficients)),
+            ),
+            escaping => (
+                "vec2(0.0)".to_string(),
+                escaping.escape_source(),
+                format!("dot(x, x) > {:?}", escaping.bailout().powi(2)),
+                // The normalized iteration count, see https://linas.org/art-gallery/escape/escape.html
+                format!(
+                    "return encode(float(iterations) + 1.0 - log(0.5 * log(dot(x, x))) / {:?}, 255.0);",
+                    escaping.degree().ln() as f32
+                ),
+            ),
+        };
+        format!(
+            r#"{complex}
+        vec2 start(vec2 point) {{
+          return {start};
+        }}
+
+        vec2 f(vec2 x, vec2 previous, vec2 c) {{
+          {next}
+        }}
+
+        bool stop(vec2 x, vec2 previous) {{
+          return {stop};
+        }}
+
+        vec4 result(vec2 x, int iterations) {{
+          {result}
+        }}
+"#,
+            complex = COMPLEX_SOURCE,
+            start = start,
+            next = next,
+            stop = stop,
+            result = result,
+        )
+    }
+
+    /// The body of `f` for the formulas whose points escape.
+    fn escape_source(&self) -> String {
+        match self {
+            // Whole powers are exact, and avoid the cut of `atan` along the negative axis
+            Formula::Power(n) if n.fract() == 0.0 => {
+                let mut source = String::from("vec2 y = x;");
+                (1..*n as u32).for_each(|_| source.push_str(" y = cmul(y, x);"));
+                source + " return y + c;"
+            }
+            Formula::Power(n) => format!("return cpow(x, {:?}) + c;", n),
+            Formula::BurningShip => String::from("vec2 y = abs(x); return cmul(y, y) + c;"),
+            Formula::Tricorn => String::from("vec2 y = vec2(x.x, -x.y); return cmul(y, y) + c;"),
+            Formula::Phoenix { p } => format!(
+                "return cmul(x, x) + c + cmul(vec2({:?}, {:?}), previous);",
+                p.0 as f32, p.1 as f32
+            ),
+            Formula::Newton(_) => unreachable!("Newton's method converges"),
+        }
+    }
+
+    /// The number of roots colored by Newton's method, 0 for the other formulas.
+    pub fn root_count(&self) -> usize {
+        match self {
+            Formula::Newton(coefficients) => coefficients.len() - 1,
+            _ => 0,
+        }
+    }
+
+    /// The radius beyond which points escape to infinity for `|c| <= 2`, at least `ESCAPE_RADIUS`.
+    fn bailout(&self) -> f64 {
+        match self {
+            // Capped so that its square fits in single precision
+            Formula::Power(n) => ESCAPE_RADIUS.max(2f64.powf(1.0 / (n - 1.0))).min(1e4),
+            _ => ESCAPE_RADIUS,
+        }
+    }
+
+    /// The degree of the formula far from 0, which is how fast escaping points go to infinity.
+    fn degree(&self) -> f64 {
+        match self {
+            Formula::Power(n) => *n,
+            _ => 2.0,
+        }
+    }
+}
+
+/// The body of `f` for Newton's method, `p` and its derivative by Horner's method.
+fn newton_source(coefficients: &[f64]) -> String {
+    let mut source = format!("vec2 p = vec2({:?}, 0.0); vec2 dp = vec2(0.0);", coefficients[0] as f32);
+    for coefficient in &coefficients[1..] {
+        source.push_str(&format!(
+            " dp = cmul(dp, x) + p; p = cmul(p, x) + vec2({:?}, 0.0);",
+            *coefficient as f32
+        ));
+    }
+    source + " return x - cdiv(p, dp);"
+}
+
+/// The body of `result` for Newton's method, the nearest root is the flag.
+fn root_source(roots: &[(f64, f64)]) -> String {
+    let mut source = String::from("float root = 0.0; float nearest = 1e30;");
+    for (i, root) in roots.iter().enumerate() {
+        source.push_str(&format!(
+            " if (distance(x, vec2({:?}, {:?})) < nearest) {{ nearest = distance(x, vec2({:?}, {:?})); root = {:?}; }}",
+            root.0 as f32, root.1 as f32, root.0 as f32, root.1 as f32, i as f32
+        ));
+    }
+    source + " return encode(float(iterations), root + 1.0);"
+}
+
+/// The complex roots of a polynomial with real coefficients, highest degree first, by the Durand-Kerner
+/// method.
+pub fn roots(coefficients: &[f64]) -> Vec<(f64, f64)> {
+    let degree = coefficients.len() - 1;
+    let monic: Vec<f64> = coefficients.iter().map(|c| c / coefficients[0]).collect();
+    let evaluate = |z: (f64, f64)| {
+        monic
+            .iter()
+            .fold((0.0, 0.0), |p, coefficient| add(mul(p, z), (*coefficient, 0.0)))
+    };
+    // Powers of a number that is neither real nor a root of unity
+    let mut roots: Vec<(f64, f64)> = (0..degree)
+        .scan((1.0, 0.0), |z, _| {
+            let root = *z;
+            *z = mul(*z, (0.4, 0.9));
+            Some(root)
+        })
+        .collect();
+    for _ in 0..500 {
+        let mut change: f64 = 0.0;
+        for i in 0..degree {
+            let denominator = (0..degree)
+                .filter(|j| *j != i)
+                .fold((1.0, 0.0), |product, j| mul(product, sub(roots[i], roots[j])));
+            let step = div(evaluate(roots[i]), denominator);
+            roots[i] = sub(roots[i], step);
+            change = change.max(step.0.abs() + step.1.abs());
+        }
+        if change < 1e-14 {
+            break;
+        }
+    }
+    roots
+}
+
+fn add(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn sub(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn mul(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+fn div(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let norm = b.0 * b.0 + b.1 * b.1;
+    ((a.0 * b.0 + a.1 * b.1) / norm, (a.1 * b.0 - a.0 * b.1) / norm)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mendelbrot::Formula;
+
+    #[test]
+    fn round_trips_the_presets() {
+        for formula in Formula::presets() {
+            assert_eq!(formula.to_string().parse::<Formula>(), Ok(formula));
+        }
+    }
+
+    #[test]
+    fn rejects_parameters_that_are_not_finite() {
+        for formula in [
+            "phoenix:inf,0",
+            "phoenix:0,NaN",
+            "newton:1,0,inf",
+            "newton:NaN,0,-1",
+            "z^inf",
+            "z^NaN",
+        ] {
+            assert!(formula.parse::<Formula>().is_err(), "{}", formula);
+        }
+    }
+
+    #[test]
+    fn newton_drops_leading_zeros() {
+        assert_eq!("newton:0,1,0,-1".parse(), Ok(Formula::Newton(vec![1.0, 0.0, -1.0])));
+        assert!("newton:0,0,1".parse::<Formula>().is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::parse_color;
+
+/// The number of texels of the palette texture.
+pub const PALETTE_SIZE: u32 = 1024;
+
+/// Colors at positions from 0 to 1, interpolated in between. The gradient wraps around, the last color
+/// blends into the first one, so that palettes can cycle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    stops: Vec<(f64, [u8; 3])>,
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        "0:#000764,0.16:#206bcb,0.42:#edffff,0.6425:#ffaa00,0.8575:#000200"
+            .parse()
+            .unwrap()
+    }
+}
+
+impl FromStr for Gradient {
+    type Err = String;
+
+    /// Comma separated stops like `0.5:#ffaa00`, the `#` is optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let stops = s
+            .split(',')
+            .map(|stop| {
+                let (position, color) = stop
+                    .split_once(':')
+                    .ok_or_else(|| format!("expected a stop like `0.5:#ffaa00`, got `{}`", stop))?;
+                let position = position
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|position| (0.0..=1.0).contains(position))
+                    .ok_or_else(|| format!("expected a position from 0 to 1, got `{}`", position))?;
+                let color = parse_color(&format!("#{}", color.trim().trim_start_matches('#')))?;
+                Ok((position, color))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Gradient::new(stops)
+    }
+}
+
+impl fmt::Display for Gradient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stops: Vec<String> = self
+            .stops
+            .iter()
+            .map(|(position, [r, g, b])| format!("{}:#{:02x}{:02x}{:02x}", position, r, g, b))
+            .collect();
+        write!(f, "{}", stops.join(","))
+    }
+}
+
+impl Gradient {
+    pub fn new(mut stops: Vec<(f64, [u8; 3])>) -> Result<Gradient, String> {
+        if stops.is_empty() {
+            return Err(String::from("a gradient needs at least one stop"));
+        }
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(Gradient { stops })
+    }
+
+    /// A gradient file, by its extension.
+    pub fn import(file_name: &str, text: &str) -> Result<Gradient, String> {
+        match file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase())
+        {
+            Some(extension) if extension == "map" => Gradient::from_map(text),
+            Some(extension) if extension == "ugr" => Gradient::from_ugr(text),
+            _ => Err(format!(
+                "unknown gradient file `{}`, expected a `.map` or `.ugr` file",
+                file_name
+            )),
+        }
+    }
+
+    /// A Fractint palette, a line of red, green and blue from 0 to 255 for every color, anything after them
+    /// is a comment.
+    pub fn from_map(text: &str) -> Result<Gradient, String> {
+        let colors = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let channels: Vec<u8> = line
+                    .split_whitespace()
+                    .take(3)
+                    .map(|channel| channel.parse::<u8>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("expected a color like `255 170 0`, got `{}`", line))?;
+                match channels.as_slice() {
+                    [r, g, b] => Ok([*r, *g, *b]),
+                    _ => Err(format!("expected a color like `255 170 0`, got `{}`", line)),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let n = colors.len() as f64;
+        Gradient::new(
+            colors
+                .into_iter()
+                .enumerate()
+                .map(|(i, color)| (i as f64 / n, color))
+                .collect(),
+        )
+    }
+
+    /// The first gradient of an Ultra Fractal gradient file, from its `index=` and `color=` pairs. Indices go
+    /// from 0 to 399 and colors are `0xbbggrr` in decimal.
+    pub fn from_ugr(text: &str) -> Result<Gradient, String> {
+        let mut stops = vec![];
+        for line in text.lines().map(str::trim) {
+            // The opacity and the other gradients come after the colors
+            if line.starts_with("opacity:") || line.starts_with('}') {
+                break;
+            }
+            let value = |key: &str| {
+                line.split_whitespace()
+                    .find_map(|pair| pair.strip_prefix(key))
+                    .map(|value| value.parse::<i64>())
+            };
+            match (value("index="), value("color=")) {
+                (Some(Ok(index)), Some(Ok(color))) => {
+                    let color = [color & 0xff, color >> 8 & 0xff, color >> 16 & 0xff].map(|channel| channel as u8);
+                    stops.push((index.rem_euclid(400) as f64 / 400.0, color));
+                }
+                (None, None) => {}
+                _ => return Err(format!("expected a stop like `index=0 color=8716288`, got `{}`", line)),
+            }
+        }
+        Gradient::new(stops)
+    }
+
+    /// The color at a position from 0 to 1.
+    pub fn color(&self, position: f64) -> [u8; 3] {
+        // The stop after the position, the first one of the next turn past the last stop
+        let next = self.stops.iter().position(|(stop, _)| *stop > position);
+        let (from, to) = match next {
+            Some(0) => {
+                let (last, color) = self.stops[self.stops.len() - 1];
+                ((last - 1.0, color), self.stops[0])
+            }
+            Some(next) => (self.stops[next - 1], self.stops[next]),
+            None => {
+                let (first, color) = self.stops[0];
+                (self.stops[self.stops.len() - 1], (first + 1.0, color))
+            }
+        };
+        let t = if to.0 > from.0 {
+            (position - from.0) / (to.0 - from.0)
+        } else {
+            0.0
+        };
+        let mut color = [0; 3];
+        (0..3).for_each(|c| color[c] = (from.1[c] as f64 + (to.1[c] as f64 - from.1[c] as f64) * t).round() as u8);
+        color
+    }
+
+    /// `PALETTE_SIZE` RGB texels of the gradient.
+    pub fn texels(&self) -> Vec<u8> {
+        (0..PALETTE_SIZE)
+            .flat_map(|i| self.color((i as f64 + 0.5) / PALETTE_SIZE as f64))
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+/// Seconds for `c` to go once around a path.
+pub const PATH_PERIOD: f64 = 20.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fractal {
+    Mandelbrot,
+    /// The Julia set of the `c` picked on the Mandelbrot set.
+    Julia,
+}
+
+/// The fractals on the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    Mandelbrot,
+    Julia,
+    /// The Mandelbrot set on the left half and the Julia set on the right half.
+    Split,
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mandelbrot" => Ok(Layout::Mandelbrot),
+            "julia" => Ok(Layout::Julia),
+            "split" => Ok(Layout::Split),
+            _ => Err(format!(
+                "unknown layout `{}`, expected `mandelbrot`, `julia` or `split`",
+                s
+            )),
+        }
+    }
+}
+
+impl Layout {
+    pub fn next(self) -> Layout {
+        match self {
+            Layout::Mandelbrot => Layout::Julia,
+            Layout::Julia => Layout::Split,
+            Layout::Split => Layout::Mandelbrot,
+        }
+    }
+
+    /// The fractal of every panel, with its left edge and width in pixels.
+    pub fn panels(self, width: f32) -> Vec<(Fractal, f32, f32)> {
+        match self {
+            Layout::Mandelbrot => vec![(Fractal::Mandelbrot, 0.0, width)],
+            Layout::Julia => vec![(Fractal::Julia, 0.0, width)],
+            Layout::Split => {
+                let half = (width / 2.0).floor();
+                vec![(Fractal::Mandelbrot, 0.0, half), (Fractal::Julia, half, width - half)]
+            }
+        }
+    }
+}
+
+/// A closed path `c` goes around to animate the Julia set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Path {
+    /// The edge of the main cardioid of the Mandelbrot set, where Julia sets change the most.
+    Cardioid,
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+    },
+}
+
+impl FromStr for Path {
+    type Err = String;
+
+    /// `cardioid`, or `circle:x,y,radius`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "cardioid" => Ok(Path::Cardioid),
+            Some(("circle", circle)) => match parse_numbers(circle)?.as_slice() {
+                [x, y, radius] => Ok(Path::Circle {
+                    center: (*x, *y),
+                    radius: *radius,
+                }),
+                _ => Err(format!("expected a circle like `circle:0,0,0.7885`, got `{}`", s)),
+            },
+            _ => Err(format!(
+                "unknown path `{}`, expected `cardioid` or `circle:x,y,radius`",
+                s
+            )),
+        }
+    }
+}
+
+impl Path {
+    /// The point at `t` from 0 to 1 along the path.
+    pub fn at(&self, t: f64) -> (f64, f64) {
+        let angle = 2.0 * PI * t;
+        match self {
+            Path::Cardioid => (
+                angle.cos() / 2.0 - (2.0 * angle).cos() / 4.0,
+                angle.sin() / 2.0 - (2.0 * angle).sin() / 4.0,
+            ),
+            Path::Circle { center, radius } => (center.0 + radius * angle.cos(), center.1 + radius * angle.sin()),
+        }
+    }
+}
+
+/// A complex number written `x,y`.
+pub fn parse_point(s: &str) -> Result<(f64, f64), String> {
+    match parse_numbers(s)?.as_slice() {
+        [x, y] => Ok((*x, *y)),
+        _ => Err(format!("expected a point like `-0.8,0.156`, got `{}`", s)),
+    }
+}
+
+pub(super) fn parse_numbers(s: &str) -> Result<Vec<f64>, String> {
+    s.split(',')
+        .map(|number| {
+            number
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| format!("invalid number `{}`", number))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mendelbrot::{parse_point, Path};
+
+    #[test]
+    fn parses_points() {
+        assert_eq!(parse_point(" -0.8, 0.156 "), Ok((-0.8, 0.156)));
+        assert!(parse_point("1").is_err());
+    }
+
+    #[test]
+    fn rejects_numbers_that_are_not_finite() {
+        for point in ["inf,0", "0,NaN", "-infinity,1"] {
+            assert!(parse_point(point).is_err(), "{}", point);
+        }
+        assert!("circle:0,0,inf".parse::<Path>().is_err());
+        assert!("circle:NaN,0,0.5".parse::<Path>().is_err());
+    }
+}
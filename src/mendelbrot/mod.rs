@@ -0,0 +1,755 @@
+mod coloring;
+mod double_float;
+mod fixed;
+mod formula;
+mod gradient;
+mod julia;
+mod perturbation;
+
+pub use coloring::*;
+pub use fixed::*;
+pub use formula::*;
+pub use gradient::*;
+pub use julia::*;
+pub use perturbation::*;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsValue;
+use web_sys::WebGlRenderingContext;
+
+use crate::dom;
+use crate::gl::{Attribute, AttributeType, Dimension, Framebuffer, Program, ProgramDescription, Texture, UniformValue};
+
+/// Below this zoom size single precision cannot tell pixels apart, and the view is rendered in double-float.
+const SINGLE_PRECISION_LIMIT: f64 = 1e-5;
+/// Below this zoom size double-float cannot tell pixels apart, and the view is rendered by perturbation.
+const DOUBLE_FLOAT_LIMIT: f64 = 1e-12;
+/// The smallest zoom size, the mantissa and exponent of a double.
+const MIN_ZOOM_SIZE: f64 = 1e-300;
+
+/// Points escape beyond this radius, far enough for the normalized iteration count to be smooth.
+const ESCAPE_RADIUS: f64 = 256.0;
+/// Palette turns per second while the palette cycles.
+const CYCLE_SPEED: f64 = 0.05;
+
+/// A program drawing a triangle over the whole canvas.
+fn full_screen_program(fragment_source: &str) -> Result<Program, JsValue> {
+    let vertex_source = r#"
+        precision highp float;
+        attribute vec2 a_position;
+        void main() {
+          gl_Position = vec4(a_position, 0.0, 1.0);
+        }
+    "#;
+    Program::new(
+        "canvas",
+        ProgramDescription {
+            vertex_source,
+            fragment_source,
+            attributes: vec![Attribute {
+                name: "a_position",
+                attribute_type: AttributeType::Vector(Dimension::D2),
+                vertices: vec![-1.0, -1.0, 3.0, -1.0, -1.0, 3.0],
+            }],
+            ..Default::default()
+        },
+    )
+}
+
+/// The fraction limbs of the center at a zoom size, enough for the pixels and the reference orbit.
+fn fraction_limbs(zoom_size: f64) -> usize {
+    ((-zoom_size.log2() + 64.0) / 32.0).ceil().max(2.0) as usize
+}
+
+/// Iterates the formula in single precision.
+fn single_precision_source(formula: &Formula) -> String {
+    format!(
+        r#"
+        precision highp float;
+
+        uniform vec2 u_origin;
+        uniform vec2 u_dimension;
+        uniform vec2 u_zoom_center;
+        uniform float u_zoom_size;
+        uniform int u_max_iterations;
+        uniform int u_julia;
+        uniform vec2 u_julia_c;
+        {encode}
+        {formula}
+        void main() {{
+          vec2 uv = (gl_FragCoord.xy - u_origin) / u_dimension;
+          vec2 point = u_zoom_center + (uv * 4.0 - vec2(2.0)) * (u_zoom_size / 4.0);
+          vec2 x = u_julia == 1 ? point : start(point);
+          vec2 c = u_julia == 1 ? u_julia_c : point;
+          vec2 previous = vec2(0.0);
+          bool stopped = false;
+          int iterations = 0;
+          for (int i = 0; i < 10000; i++) {{
+            if (i > u_max_iterations) break;
+            iterations = i;
+            vec2 next = f(x, previous, c);
+            previous = x;
+            x = next;
+            if (stop(x, previous)) {{
+              stopped = true;
+              break;
+            }}
+          }}
+          gl_FragColor = stopped ? result(x, iterations) : vec4(0.0);
+        }}
+    "#,
+        encode = ENCODE_SOURCE,
+        formula = formula.source(),
+    )
+}
+
+/// Renders by perturbation the zooms single precision cannot.
+struct DeepZoom {
+    program: Program,
+    orbit_texture: Texture,
+    /// The point of the orbit in the texture, `None` until the first deep zoom.
+    reference: Option<(Fixed, Fixed)>,
+    orbit_length: usize,
+    orbit_rows: u32,
+    /// The maximum of iterations the orbit was computed for.
+    orbit_iterations: i32,
+}
+
+impl DeepZoom {
+    /// The offset of the reference point from the center of the view, in units of the zoom size.
+    fn reference_offset(&self, view: &View) -> Option<[f32; 2]> {
+        let reference = self.reference.as_ref()?;
+        Some([
+            ((&reference.0 - &view.zoom_center.0).to_f64() / view.zoom_size) as f32,
+            ((&reference.1 - &view.zoom_center.1).to_f64() / view.zoom_size) as f32,
+        ])
+    }
+
+    /// Whether the orbit in the texture can render the view: its point is still inside the view, as
+    /// precise as the center needs to be, and it was computed for as many iterations.
+    fn can_render(&self, view: &View) -> bool {
+        match (self.reference.as_ref(), self.reference_offset(view)) {
+            (Some(reference), Some([x, y])) => {
+                x.abs() <= 0.5
+                    && y.abs() <= 0.5
+                    && reference.0.fraction_limbs() >= fraction_limbs(view.zoom_size)
+                    && self.orbit_iterations >= view.max_iterations
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The zoom of one fractal, zooming towards a target while the mouse button is toggled.
+struct View {
+    zoom_center: (Fixed, Fixed),
+    target_zoom_center: (Fixed, Fixed),
+    zoom_size: f64,
+    zoom_factor: f64,
+    max_iterations: i32,
+    zooming: bool,
+    /// The smallest zoom size the view can be rendered at.
+    min_zoom_size: f64,
+}
+
+impl View {
+    fn new(min_zoom_size: f64) -> View {
+        let center = (Fixed::zero(2), Fixed::zero(2));
+        View {
+            zoom_center: center.clone(),
+            target_zoom_center: center,
+            zoom_size: 4.0,
+            zoom_factor: 1.0,
+            max_iterations: 500,
+            zooming: false,
+            min_zoom_size,
+        }
+    }
+
+    pub fn toggle_zooming(&mut self, (x, y): (f32, f32)) {
+        self.zooming = !self.zooming;
+        if self.zooming {
+            self.set_target_zoom_center((x, y));
+            self.zoom_factor = 0.96;
+            self.max_iterations = 50;
+        } else {
+            // Points of deeper views take longer to escape
+            let depth = (4.0 / self.zoom_size).log2();
+            self.max_iterations = (30.0 * depth).clamp(1000.0, 10000.0) as i32;
+            self.zoom_factor = 1.0;
+        }
+    }
+
+    pub fn set_target_zoom_center(&mut self, (x, y): (f32, f32)) {
+        let limbs = fraction_limbs(self.zoom_size);
+        let (dx, dy) = ((x as f64 - 0.5) * self.zoom_size, (0.5 - y as f64) * self.zoom_size);
+        self.target_zoom_center.0 = &self.zoom_center.0 + &Fixed::from_f64(dx, limbs);
+        self.target_zoom_center.1 = &self.zoom_center.1 + &Fixed::from_f64(dy, limbs);
+    }
+
+    /// The point at `(x, y)` from the top left corner, in fractions of the view.
+    pub fn point(&self, (x, y): (f32, f32)) -> (f64, f64) {
+        (
+            self.zoom_center.0.to_f64() + (x as f64 - 0.5) * self.zoom_size,
+            self.zoom_center.1.to_f64() + (0.5 - y as f64) * self.zoom_size,
+        )
+    }
+
+    /// Zooms out to the smallest zoom size if the view is deeper.
+    pub fn set_min_zoom_size(&mut self, min_zoom_size: f64) {
+        self.min_zoom_size = min_zoom_size;
+        self.zoom_size = self.zoom_size.max(min_zoom_size);
+    }
+
+    pub fn step(&mut self) {
+        if self.zooming {
+            self.zoom_size = (self.zoom_size * self.zoom_factor).max(self.min_zoom_size);
+            // Deeper zooms need more precise centers
+            let limbs = fraction_limbs(self.zoom_size);
+            let move_towards = |center: &mut Fixed, target: &mut Fixed| {
+                if center.fraction_limbs() < limbs {
+                    *center = center.with_fraction_limbs(limbs);
+                    *target = target.with_fraction_limbs(limbs);
+                }
+                *center = &*center + &Fixed::from_f64(0.1 * (&*target - &*center).to_f64(), limbs);
+            };
+            move_towards(&mut self.zoom_center.0, &mut self.target_zoom_center.0);
+            move_towards(&mut self.zoom_center.1, &mut self.target_zoom_center.1);
+        }
+    }
+}
+
+/// What the iterations of a panel depend on, its histogram is only computed again when they change.
+#[derive(Clone, PartialEq)]
+struct HistogramKey {
+    fractal: Fractal,
+    zoom_center: (Fixed, Fixed),
+    zoom_size: f64,
+    max_iterations: i32,
+    /// Only for Julia sets.
+    julia_c: Option<(f64, f64)>,
+    formula: Formula,
+    origin: [f32; 2],
+    dimension: [f32; 2],
+}
+
+/// The histogram texels of a panel, with what they were computed from.
+type PanelHistogram = (HistogramKey, Vec<u8>);
+
+/// Where a fractal is drawn on the canvas, in pixels from the bottom left corner.
+struct Panel {
+    fractal: Fractal,
+    origin: [f32; 2],
+    dimension: [f32; 2],
+}
+
+struct App {
+    program: Program,
+    double_float_program: Program,
+    /// Missing without float textures, the zoom then stops at the double-float limit.
+    deep_zoom: Option<DeepZoom>,
+    coloring_program: Program,
+    /// The encoded iterations of every pixel, which `coloring_program` colors.
+    iterations: Framebuffer,
+    palette: Texture,
+    histogram: Texture,
+    /// The histogram of every panel.
+    histograms: RefCell<Vec<Option<PanelHistogram>>>,
+
+    coloring: Coloring,
+    gradient: Gradient,
+    /// Palette turns per second, 0 when the palette does not cycle.
+    cycle_speed: f64,
+    palette_offset: f64,
+
+    formula: Formula,
+    layout: Layout,
+    mandelbrot: View,
+    /// Julia sets are not rendered by perturbation, their orbits do not start at 0.
+    julia: View,
+    julia_c: (f64, f64),
+    /// Whether `c` stays where it was clicked instead of following the mouse over the Mandelbrot set.
+    pinned: bool,
+    path: Path,
+    /// The time along the path, in seconds, while `c` is animated.
+    path_time: Option<f64>,
+}
+
+impl App {
+    pub fn new() -> Result<App, JsValue> {
+        let formula = Formula::default();
+        let program = full_screen_program(&single_precision_source(&formula))?;
+        let double_float_program = full_screen_program(&double_float::fragment_source(ENCODE_SOURCE))?;
+
+        // The reference orbit is a float texture
+        let deep_zoom = match program.gl.get_extension("OES_texture_float") {
+            Ok(Some(_)) => Some(DeepZoom {
+                program: full_screen_program(&perturbation::fragment_source(ENCODE_SOURCE))?,
+                orbit_texture: Texture::new(&program.gl, 0)?,
+                reference: None,
+                orbit_length: 0,
+                orbit_rows: 0,
+                orbit_iterations: 0,
+            }),
+            _ => None,
+        };
+
+        let gl = &program.gl;
+        let coloring_program = full_screen_program(&coloring_source())?;
+        let canvas = dom::canvas("canvas");
+        let iterations = Framebuffer::new(gl, 1, (canvas.width(), canvas.height()), None)?;
+        let gradient = Gradient::default();
+        let palette = Texture::new(gl, 2)?;
+        palette.upload(gl, WebGlRenderingContext::RGB, (PALETTE_SIZE, 1), &gradient.texels())?;
+        let histogram = Texture::new(gl, 3)?;
+        histogram.upload(
+            gl,
+            WebGlRenderingContext::RGBA,
+            (HISTOGRAM_BINS, 1),
+            &histogram_texels(&[], 1),
+        )?;
+
+        let min_zoom_size = if deep_zoom.is_some() {
+            MIN_ZOOM_SIZE
+        } else {
+            DOUBLE_FLOAT_LIMIT
+        };
+
+        Ok(App {
+            program,
+            double_float_program,
+            deep_zoom,
+            coloring_program,
+            iterations,
+            palette,
+            histogram,
+            histograms: RefCell::new(vec![]),
+            coloring: Coloring::Smooth,
+            gradient,
+            cycle_speed: 0.0,
+            palette_offset: 0.0,
+            formula,
+            layout: Layout::Mandelbrot,
+            mandelbrot: View::new(min_zoom_size),
+            julia: View::new(DOUBLE_FLOAT_LIMIT),
+            julia_c: (-0.8, 0.156),
+            pinned: false,
+            path: Path::Cardioid,
+            path_time: None,
+        })
+    }
+
+    /// Switches the iteration function, only `z^2 + c` zooms deeper than single precision.
+    pub fn set_formula(&mut self, formula: Formula) -> Result<(), JsValue> {
+        let program = full_screen_program(&single_precision_source(&formula))?;
+        self.program.gl.delete_program(Some(&self.program.program));
+        self.program = program;
+        let (mandelbrot, julia) = match (formula.is_quadratic(), self.deep_zoom.is_some()) {
+            (true, true) => (MIN_ZOOM_SIZE, DOUBLE_FLOAT_LIMIT),
+            (true, false) => (DOUBLE_FLOAT_LIMIT, DOUBLE_FLOAT_LIMIT),
+            (false, _) => (SINGLE_PRECISION_LIMIT, SINGLE_PRECISION_LIMIT),
+        };
+        self.mandelbrot.set_min_zoom_size(mandelbrot);
+        self.julia.set_min_zoom_size(julia);
+        self.formula = formula;
+        Ok(())
+    }
+
+    pub fn set_gradient(&mut self, gradient: Gradient) -> Result<(), JsValue> {
+        self.palette.upload(
+            &self.program.gl,
+            WebGlRenderingContext::RGB,
+            (PALETTE_SIZE, 1),
+            &gradient.texels(),
+        )?;
+        self.gradient = gradient;
+        Ok(())
+    }
+
+    pub fn toggle_cycling(&mut self) {
+        self.cycle_speed = if self.cycle_speed == 0.0 { CYCLE_SPEED } else { 0.0 };
+    }
+
+    fn view(&self, fractal: Fractal) -> &View {
+        match fractal {
+            Fractal::Mandelbrot => &self.mandelbrot,
+            Fractal::Julia => &self.julia,
+        }
+    }
+
+    fn panels(&self) -> Vec<Panel> {
+        let canvas = dom::canvas("canvas");
+        let height = canvas.height() as f32;
+        self.layout
+            .panels(canvas.width() as f32)
+            .into_iter()
+            .map(|(fractal, left, width)| Panel {
+                fractal,
+                origin: [left, 0.0],
+                dimension: [width, height],
+            })
+            .collect()
+    }
+
+    /// The panel at `(x, y)` pixels from the top left corner of the canvas, and the point in fractions of it.
+    fn panel_at(&self, (x, y): (f32, f32)) -> Option<(Fractal, (f32, f32))> {
+        self.panels()
+            .into_iter()
+            .find(|panel| x >= panel.origin[0] && x < panel.origin[0] + panel.dimension[0])
+            .map(|panel| {
+                let point = ((x - panel.origin[0]) / panel.dimension[0], y / panel.dimension[1]);
+                (panel.fractal, point)
+            })
+    }
+
+    /// Zooms, or with shift picks `c` on the Mandelbrot set: pins it in the split view, or shows its Julia
+    /// set. Shift clicking a pinned `c` unpins it.
+    pub fn click(&mut self, position: (f32, f32), shift: bool) {
+        match self.panel_at(position) {
+            Some((Fractal::Mandelbrot, point)) if shift => {
+                if self.pinned && self.layout == Layout::Split {
+                    self.pinned = false;
+                } else {
+                    self.julia_c = self.mandelbrot.point(point);
+                    self.pinned = true;
+                    self.path_time = None;
+                    if self.layout == Layout::Mandelbrot {
+                        self.layout = Layout::Julia;
+                    }
+                }
+            }
+            Some((Fractal::Mandelbrot, point)) => self.mandelbrot.toggle_zooming(point),
+            Some((Fractal::Julia, point)) => self.julia.toggle_zooming(point),
+            None => {}
+        }
+    }
+
+    /// Picks `c` under the mouse in the split view, unless it is pinned or animated.
+    pub fn hover(&mut self, position: (f32, f32)) {
+        if self.pinned || self.path_time.is_some() || self.layout != Layout::Split {
+            return;
+        }
+        if let Some((Fractal::Mandelbrot, point)) = self.panel_at(position) {
+            self.julia_c = self.mandelbrot.point(point);
+        }
+    }
+
+    pub fn toggle_animation(&mut self) {
+        self.path_time = match self.path_time {
+            Some(_) => None,
+            None => Some(0.0),
+        };
+    }
+
+    pub fn step(&mut self, dt: f64) -> Result<(), JsValue> {
+        self.resize()?;
+        self.palette_offset = (self.palette_offset + dt * self.cycle_speed).fract();
+        self.mandelbrot.step();
+        self.julia.step();
+        if let Some(time) = self.path_time.as_mut() {
+            *time += dt;
+            self.julia_c = self.path.at(*time / PATH_PERIOD % 1.0);
+        }
+        if self.layout != Layout::Julia {
+            self.update_orbit()?;
+        }
+        Ok(())
+    }
+
+    /// Reallocates the iterations when the canvas changed size.
+    fn resize(&mut self) -> Result<(), JsValue> {
+        let canvas = dom::canvas("canvas");
+        let size = (canvas.width(), canvas.height());
+        if self.iterations.size != size {
+            let gl = &self.program.gl;
+            gl.delete_framebuffer(Some(&self.iterations.framebuffer));
+            gl.delete_texture(Some(&self.iterations.texture.texture));
+            self.iterations = Framebuffer::new(gl, 1, size, None)?;
+        }
+        Ok(())
+    }
+
+    /// Computes a reference orbit for deep zooms, when the last one cannot render the view anymore.
+    fn update_orbit(&mut self) -> Result<(), JsValue> {
+        let view = &self.mandelbrot;
+        let deep_zoom = match self.deep_zoom.as_mut() {
+            Some(deep_zoom) if view.zoom_size < DOUBLE_FLOAT_LIMIT => deep_zoom,
+            _ => return Ok(()),
+        };
+        if deep_zoom.can_render(view) {
+            return Ok(());
+        }
+        let (reference, orbit) = longest_orbit(&view.zoom_center, view.zoom_size, view.max_iterations as usize);
+        let (texels, rows) = orbit_texels(&orbit);
+        let gl = &deep_zoom.program.gl;
+        deep_zoom
+            .orbit_texture
+            .upload_f32(gl, WebGlRenderingContext::RGBA, (ORBIT_WIDTH, rows), &texels)?;
+        deep_zoom.reference = Some(reference);
+        deep_zoom.orbit_length = orbit.len();
+        deep_zoom.orbit_rows = rows;
+        deep_zoom.orbit_iterations = view.max_iterations;
+        Ok(())
+    }
+
+    /// Renders the iterations of every panel, and then colors them on the canvas.
+    pub fn render(&self) -> Result<(), JsValue> {
+        let gl = &self.program.gl;
+        Program::clear_gl(gl);
+        for (i, panel) in self.panels().into_iter().enumerate() {
+            let view = self.view(panel.fractal);
+            let (x, y) = (panel.origin[0] as i32, panel.origin[1] as i32);
+            let (width, height) = (panel.dimension[0] as i32, panel.dimension[1] as i32);
+
+            self.iterations.bind(gl);
+            gl.viewport(x, y, width, height);
+            // The encoded iterations are not colors, they must be written as they are
+            gl.disable(WebGlRenderingContext::BLEND);
+            match &self.deep_zoom {
+                Some(deep_zoom) if panel.fractal == Fractal::Mandelbrot && view.zoom_size < DOUBLE_FLOAT_LIMIT => {
+                    self.render_deep_zoom(deep_zoom, view, &panel)
+                }
+                _ if view.zoom_size < SINGLE_PRECISION_LIMIT => self.render_double_float(view, &panel),
+                _ => self.render_single(view, &panel),
+            }
+            if self.coloring == Coloring::Histogram {
+                let key = HistogramKey {
+                    fractal: panel.fractal,
+                    zoom_center: view.zoom_center.clone(),
+                    zoom_size: view.zoom_size,
+                    max_iterations: view.max_iterations,
+                    julia_c: Some(self.julia_c).filter(|_| panel.fractal == Fractal::Julia),
+                    formula: self.formula.clone(),
+                    origin: panel.origin,
+                    dimension: panel.dimension,
+                };
+                let mut histograms = self.histograms.borrow_mut();
+                if histograms.len() <= i {
+                    histograms.resize(i + 1, None);
+                }
+                if histograms[i].as_ref().map(|(computed, _)| computed) != Some(&key) {
+                    let mut pixels = vec![0; 4 * (width * height) as usize];
+                    gl.read_pixels_with_opt_u8_array(
+                        x,
+                        y,
+                        width,
+                        height,
+                        WebGlRenderingContext::RGBA,
+                        WebGlRenderingContext::UNSIGNED_BYTE,
+                        Some(&mut pixels),
+                    )?;
+                    histograms[i] = Some((key, histogram_texels(&pixels, view.max_iterations)));
+                }
+                let (_, texels) = histograms[i].as_ref().unwrap();
+                self.histogram
+                    .upload(gl, WebGlRenderingContext::RGBA, (HISTOGRAM_BINS, 1), texels)?;
+            }
+            gl.enable(WebGlRenderingContext::BLEND);
+            Framebuffer::unbind(gl);
+
+            gl.viewport(x, y, width, height);
+            self.render_colors(view);
+        }
+        Ok(())
+    }
+
+    fn render_colors(&self, view: &View) {
+        let program = &self.coloring_program;
+        program.prepare_render();
+        [&self.iterations.texture, &self.palette, &self.histogram]
+            .iter()
+            .for_each(|texture| texture.bind(&program.gl));
+        let size = [self.iterations.size.0 as f32, self.iterations.size.1 as f32];
+        program.set_uniform("u_size", UniformValue::Vector2(size));
+        program.set_uniform("u_iterations", UniformValue::Int(self.iterations.texture.unit as i32));
+        program.set_uniform("u_palette", UniformValue::Int(self.palette.unit as i32));
+        program.set_uniform("u_histogram", UniformValue::Int(self.histogram.unit as i32));
+        program.set_uniform("u_coloring", UniformValue::Int(self.coloring as i32));
+        program.set_uniform("u_max_iterations", UniformValue::Float(view.max_iterations as f32));
+        program.set_uniform("u_roots", UniformValue::Float(self.formula.root_count().max(1) as f32));
+        program.set_uniform("u_offset", UniformValue::Float(self.palette_offset as f32));
+        program.render();
+    }
+
+    fn set_panel_uniforms(&self, program: &Program, panel: &Panel) {
+        program.set_uniform("u_origin", UniformValue::Vector2(panel.origin));
+        program.set_uniform("u_dimension", UniformValue::Vector2(panel.dimension));
+        program.set_uniform("u_julia", UniformValue::Int((panel.fractal == Fractal::Julia) as i32));
+        program.set_uniform(
+            "u_julia_c",
+            UniformValue::Vector2([self.julia_c.0 as f32, self.julia_c.1 as f32]),
+        );
+    }
+
+    fn render_single(&self, view: &View, panel: &Panel) {
+        self.program.prepare_render();
+        self.set_panel_uniforms(&self.program, panel);
+        self.program.set_uniform(
+            "u_zoom_center",
+            UniformValue::Vector2([view.zoom_center.0.to_f64() as f32, view.zoom_center.1.to_f64() as f32]),
+        );
+        self.program
+            .set_uniform("u_zoom_size", UniformValue::Float(view.zoom_size as f32));
+        self.program
+            .set_uniform("u_max_iterations", UniformValue::Int(view.max_iterations));
+        self.program.render();
+    }
+
+    fn render_double_float(&self, view: &View, panel: &Panel) {
+        let program = &self.double_float_program;
+        program.prepare_render();
+        self.set_panel_uniforms(program, panel);
+        program.set_uniform(
+            "u_zoom_center_x",
+            UniformValue::Vector2(double_float::split(view.zoom_center.0.to_f64())),
+        );
+        program.set_uniform(
+            "u_zoom_center_y",
+            UniformValue::Vector2(double_float::split(view.zoom_center.1.to_f64())),
+        );
+        program.set_uniform("u_zoom_size", UniformValue::Float(view.zoom_size as f32));
+        program.set_uniform("u_max_iterations", UniformValue::Int(view.max_iterations));
+        program.set_uniform("u_one", UniformValue::Float(1.0));
+        program.render();
+    }
+
+    fn render_deep_zoom(&self, deep_zoom: &DeepZoom, view: &View, panel: &Panel) {
+        let program = &deep_zoom.program;
+        let (mantissa, exponent) = float_exp(view.zoom_size);
+        program.prepare_render();
+        deep_zoom.orbit_texture.bind(&program.gl);
+        program.set_uniform("u_origin", UniformValue::Vector2(panel.origin));
+        program.set_uniform("u_dimension", UniformValue::Vector2(panel.dimension));
+        let reference = deep_zoom.reference_offset(view).unwrap_or([0.0, 0.0]);
+        program.set_uniform("u_reference", UniformValue::Vector2(reference));
+        program.set_uniform("u_zoom_mantissa", UniformValue::Float(mantissa));
+        program.set_uniform("u_zoom_exponent", UniformValue::Float(exponent));
+        program.set_uniform("u_max_iterations", UniformValue::Int(view.max_iterations));
+        program.set_uniform("u_orbit", UniformValue::Int(deep_zoom.orbit_texture.unit as i32));
+        program.set_uniform("u_orbit_length", UniformValue::Int(deep_zoom.orbit_length as i32));
+        program.set_uniform("u_orbit_rows", UniformValue::Float(deep_zoom.orbit_rows as f32));
+        program.render();
+    }
+}
+
+/// Click to zoom in and out. Shift click the Mandelbrot set to see the Julia set of the point, `j` switches
+/// between the Mandelbrot set, the Julia set and both side by side, where hovering the Mandelbrot set picks
+/// the Julia set. `a` animates `c` along the path. `f` switches to the next formula, see `Formula`. `m`
+/// switches between the colorings, see `Coloring`, and `c` cycles the palette.
+#[wasm_bindgen]
+#[allow(dead_code)]
+pub fn mendelbrot(
+    layout: Option<String>,
+    c: Option<String>,
+    path: Option<String>,
+    formula: Option<String>,
+) -> Result<Mendelbrot, JsValue> {
+    let mut app = App::new()?;
+    if let Some(formula) = formula {
+        app.set_formula(formula.parse()?)?;
+    }
+    if let Some(layout) = layout {
+        app.layout = layout.parse()?;
+    }
+    if let Some(c) = c {
+        app.julia_c = parse_point(&c)?;
+        app.pinned = true;
+    }
+    if let Some(path) = path {
+        app.path = path.parse()?;
+        app.toggle_animation();
+    }
+    app.render()?;
+
+    let app = Rc::new(RefCell::new(app));
+
+    let canvas = dom::canvas("canvas");
+
+    {
+        let app = app.clone();
+        dom::add_mouse_event_listener(&canvas, "click", move |e| {
+            let position = (e.offset_x() as f32, e.offset_y() as f32);
+            app.borrow_mut().click(position, e.shift_key());
+        });
+    }
+
+    {
+        let app = app.clone();
+        dom::add_mouse_event_listener(&canvas, "mousemove", move |e| {
+            app.borrow_mut().hover((e.offset_x() as f32, e.offset_y() as f32));
+        });
+    }
+
+    {
+        let app = app.clone();
+        dom::add_keyboard_event_listener("keydown", move |e| {
+            let mut app = app.borrow_mut();
+            match e.key().as_str() {
+                "j" => app.layout = app.layout.next(),
+                "a" => app.toggle_animation(),
+                "m" => app.coloring = app.coloring.next(),
+                "c" => app.toggle_cycling(),
+                "f" => {
+                    let formula = app.formula.next();
+                    if let Err(error) = app.set_formula(formula) {
+                        web_sys::console::error_1(&error);
+                    }
+                }
+                _ => {}
+            }
+        });
+    }
+
+    {
+        let app = app.clone();
+        dom::request_animation_frame(move |_t, dt| {
+            if let Err(error) = app.borrow_mut().step(dt as f64) {
+                web_sys::console::error_1(&error);
+            }
+            if let Err(error) = app.borrow().render() {
+                web_sys::console::error_1(&error);
+            }
+        });
+    }
+
+    Ok(Mendelbrot { app })
+}
+
+/// A running Mandelbrot view.
+#[wasm_bindgen]
+pub struct Mendelbrot {
+    app: Rc<RefCell<App>>,
+}
+
+#[wasm_bindgen]
+impl Mendelbrot {
+    /// The stops of the gradient, as `set_gradient` takes them.
+    pub fn gradient(&self) -> String {
+        self.app.borrow().gradient.to_string()
+    }
+
+    pub fn set_gradient(&self, stops: &str) -> Result<(), JsValue> {
+        self.app.borrow_mut().set_gradient(stops.parse()?)
+    }
+
+    /// Loads a `.map` or `.ugr` gradient file.
+    pub fn import_gradient(&self, file_name: &str, text: &str) -> Result<(), JsValue> {
+        self.app.borrow_mut().set_gradient(Gradient::import(file_name, text)?)
+    }
+
+    pub fn set_coloring(&self, coloring: &str) -> Result<(), JsValue> {
+        self.app.borrow_mut().coloring = coloring.parse()?;
+        Ok(())
+    }
+
+    /// Palette turns per second, 0 stops the cycling.
+    pub fn set_cycle_speed(&self, speed: f64) -> Result<(), JsValue> {
+        if !speed.is_finite() {
+            return Err(JsValue::from(format!("invalid cycle speed {}", speed)));
+        }
+        self.app.borrow_mut().cycle_speed = speed;
+        Ok(())
+    }
+}
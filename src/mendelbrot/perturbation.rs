@@ -0,0 +1,236 @@
+use crate::mendelbrot::{Fixed, ESCAPE_RADIUS};
+
+/// The number of orbit points in a row of the orbit texture.
+pub const ORBIT_WIDTH: u32 = 1024;
+
+/// Below this exponent, deltas are kept in floatexp, they would underflow in single precision.
+const FLOATEXP_EXPONENT: f32 = -80.0;
+
+/// The orbit of the reference point `c` from `z = 0`, computed at the precision of `c` and rounded to single
+/// precision, until it escapes or for `max_iterations`.
+pub fn reference_orbit(c: &(Fixed, Fixed), max_iterations: usize) -> Vec<[f32; 2]> {
+    let fraction_limbs = c.0.fraction_limbs().max(c.1.fraction_limbs());
+    let (mut x, mut y) = (Fixed::zero(fraction_limbs), Fixed::zero(fraction_limbs));
+    let mut orbit = vec![[0.0, 0.0]];
+    for _ in 0..max_iterations.max(1) {
+        let (xx, yy, xy) = (&x * &x, &y * &y, &x * &y);
+        x = &(&xx - &yy) + &c.0;
+        y = &(&xy + &xy) + &c.1;
+        let (re, im) = (x.to_f64(), y.to_f64());
+        orbit.push([re as f32, im as f32]);
+        if re * re + im * im > ESCAPE_RADIUS * ESCAPE_RADIUS {
+            break;
+        }
+    }
+    orbit
+}
+
+/// The longest orbit among the center and a grid of points around it `zoom_size` apart, with its point.
+/// Rebasing on the start of an orbit that escapes early loses precision.
+pub fn longest_orbit(
+    center: &(Fixed, Fixed),
+    zoom_size: f64,
+    max_iterations: usize,
+) -> ((Fixed, Fixed), Vec<[f32; 2]>) {
+    let fraction_limbs = center.0.fraction_limbs().max(center.1.fraction_limbs());
+    let mut longest = (center.clone(), reference_orbit(center, max_iterations));
+    let offsets = [-0.4, -0.2, 0.0, 0.2, 0.4];
+    for offset in offsets.iter().flat_map(|x| offsets.iter().map(move |y| [*x, *y])) {
+        if longest.1.len() > max_iterations {
+            break;
+        }
+        let c = (
+            &center.0 + &Fixed::from_f64(offset[0] * zoom_size, fraction_limbs),
+            &center.1 + &Fixed::from_f64(offset[1] * zoom_size, fraction_limbs),
+        );
+        let orbit = reference_orbit(&c, max_iterations);
+        if orbit.len() > longest.1.len() {
+            longest = (c, orbit);
+        }
+    }
+    longest
+}
+
+/// The orbit as RGBA texels, `ORBIT_WIDTH` per row, and the number of rows.
+pub fn orbit_texels(orbit: &[[f32; 2]]) -> (Vec<f32>, u32) {
+    let rows = (orbit.len() as u32).div_ceil(ORBIT_WIDTH);
+    let mut texels = vec![0.0; 4 * (ORBIT_WIDTH * rows) as usize];
+    for (texel, z) in texels.chunks_mut(4).zip(orbit.iter()) {
+        texel[..2].copy_from_slice(z);
+    }
+    (texels, rows)
+}
+
+/// `x` as a mantissa from 1 to 2 and a power of two, for numbers smaller than single precision allows.
+pub fn float_exp(x: f64) -> (f32, f32) {
+    if x == 0.0 {
+        return (0.0, 0.0);
+    }
+    let exponent = x.abs().log2().floor();
+    ((x / 2f64.powf(exponent)) as f32, exponent as f32)
+}
+
+/// Iterates the difference between every pixel and the reference orbit, see
+/// https://mathr.co.uk/blog/2021-05-14_deep_zoom_theory_and_practice.html
+///
+/// A delta starts in floatexp, a mantissa and an exponent, until it is large enough for single precision.
+/// When the pixel gets closer to 0 than to the reference orbit, or the reference orbit ends, the delta is
+/// rebased on the start of the orbit, which detects and avoids glitches.
+pub fn fragment_source(encode: &str) -> String {
+    format!(
+        r#"
+        precision highp float;
+
+        uniform vec2 u_origin;
+        uniform vec2 u_dimension;
+        uniform vec2 u_reference;
+        uniform float u_zoom_mantissa;
+        uniform float u_zoom_exponent;
+        uniform int u_max_iterations;
+        uniform sampler2D u_orbit;
+        uniform int u_orbit_length;
+        uniform float u_orbit_rows;
+
+        const float ORBIT_WIDTH = {orbit_width:?};
+        const float FLOATEXP_EXPONENT = {floatexp_exponent:?};
+        const float BAILOUT = {bailout:?};
+        {encode}
+
+        vec2 orbit(int i) {{
+          float x = mod(float(i), ORBIT_WIDTH);
+          float y = floor(float(i) / ORBIT_WIDTH);
+          return texture2D(u_orbit, vec2((x + 0.5) / ORBIT_WIDTH, (y + 0.5) / u_orbit_rows)).xy;
+        }}
+
+        vec2 cmul(vec2 a, vec2 b) {{
+          return vec2(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+        }}
+
+        // A floatexp is a mantissa in xy and a power of two in z, zero has a very small power
+        vec3 fe_normalize(vec2 m, float e) {{
+          float size = max(abs(m.x), abs(m.y));
+          if (size == 0.0) return vec3(0.0, 0.0, -1e4);
+          float k = floor(log2(size)) + 1.0;
+          // In two steps, 2^-k alone overflows for subnormal mantissas
+          float half_k = floor(k / 2.0);
+          return vec3(m * exp2(-half_k) * exp2(half_k - k), e + k);
+        }}
+
+        vec3 fe_add(vec3 a, vec3 b) {{
+          return a.z >= b.z
+            ? fe_normalize(a.xy + b.xy * exp2(b.z - a.z), a.z)
+            : fe_normalize(b.xy + a.xy * exp2(a.z - b.z), b.z);
+        }}
+
+        vec2 fe_to_float(vec3 a) {{
+          return a.z < -149.0 ? vec2(0.0) : a.xy * exp2(a.z);
+        }}
+
+        void main() {{
+          vec2 uv = (gl_FragCoord.xy - u_origin) / u_dimension;
+          vec3 dc = fe_normalize((uv - vec2(0.5) - u_reference) * u_zoom_mantissa, u_zoom_exponent);
+          vec2 dc_float = fe_to_float(dc);
+          vec3 d_exp = vec3(0.0, 0.0, -1e4);
+          vec2 d = vec2(0.0);
+          bool small = true;
+          int m = 0;
+          bool escaped = false;
+          int iterations = 0;
+          float size = 0.0;
+          for (int i = 0; i < 10000; i++) {{
+            if (i > u_max_iterations) break;
+            iterations = i;
+            vec2 reference = orbit(m);
+            m++;
+            vec2 z;
+            if (small) {{
+              // (2Z + d)d + dc
+              vec3 d2 = fe_normalize(cmul(d_exp.xy, d_exp.xy), 2.0 * d_exp.z);
+              d_exp = fe_add(fe_add(fe_normalize(cmul(2.0 * reference, d_exp.xy), d_exp.z), d2), dc);
+              d = fe_to_float(d_exp);
+              z = orbit(m) + d;
+              if (d_exp.z > FLOATEXP_EXPONENT) {{
+                small = false;
+              }} else if (dot(z, z) < dot(d, d) || m == u_orbit_length - 1) {{
+                d_exp = fe_add(fe_normalize(orbit(m), 0.0), d_exp);
+                m = 0;
+              }}
+            }} else {{
+              d = cmul(2.0 * reference + d, d) + dc_float;
+              z = orbit(m) + d;
+            }}
+            size = dot(z, z);
+            if (size > BAILOUT) {{
+              escaped = true;
+              break;
+            }}
+            if (!small && (dot(z, z) < dot(d, d) || m == u_orbit_length - 1)) {{
+              d = z;
+              m = 0;
+            }}
+          }}
+          gl_FragColor = escaped ? encode(float(iterations) + 1.0 - log2(0.5 * log(size)), 255.0) : vec4(0.0);
+        }}
+        "#,
+        orbit_width = ORBIT_WIDTH as f32,
+        floatexp_exponent = FLOATEXP_EXPONENT,
+        bailout = (ESCAPE_RADIUS * ESCAPE_RADIUS) as f32,
+        encode = encode,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mendelbrot::{float_exp, longest_orbit, orbit_texels, reference_orbit, Fixed, ORBIT_WIDTH};
+
+    fn point(x: f64, y: f64) -> (Fixed, Fixed) {
+        (Fixed::from_f64(x, 2), Fixed::from_f64(y, 2))
+    }
+
+    #[test]
+    fn orbits_inside_the_set_last_for_every_iteration() {
+        let orbit = reference_orbit(&point(-1.0, 0.0), 100);
+        assert_eq!(orbit.len(), 101);
+        assert_eq!(&orbit[..4], &[[0.0, 0.0], [-1.0, 0.0], [0.0, 0.0], [-1.0, 0.0]]);
+    }
+
+    #[test]
+    fn orbits_stop_when_they_escape() {
+        // 0, 1, 2, 5, 26, 677, which is beyond the escape radius
+        let orbit = reference_orbit(&point(1.0, 0.0), 100);
+        assert_eq!(
+            orbit.iter().map(|z| z[0]).collect::<Vec<_>>(),
+            [0.0, 1.0, 2.0, 5.0, 26.0, 677.0]
+        );
+    }
+
+    #[test]
+    fn longest_orbit_moves_off_escaping_centers() {
+        // The center escapes, the points 0.4 of the zoom size to the left are in the main cardioid
+        let (reference, orbit) = longest_orbit(&point(0.3, 0.0), 0.5, 100);
+        assert_eq!(orbit.len(), 101);
+        assert!(reference.0.to_f64() < 0.3);
+    }
+
+    #[test]
+    fn orbit_texels_fill_rows() {
+        let orbit: Vec<[f32; 2]> = (0..ORBIT_WIDTH + 1).map(|i| [i as f32, -(i as f32)]).collect();
+        let (texels, rows) = orbit_texels(&orbit);
+        assert_eq!(rows, 2);
+        assert_eq!(texels.len(), 8 * ORBIT_WIDTH as usize);
+        assert_eq!(&texels[4..8], &[1.0, -1.0, 0.0, 0.0]);
+        assert_eq!(&texels[4 * ORBIT_WIDTH as usize..][..4], &[1024.0, -1024.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn float_exp_splits_tiny_numbers() {
+        assert_eq!(float_exp(0.0), (0.0, 0.0));
+        assert_eq!(float_exp(-3.0), (-1.5, 1.0));
+        for x in [1e-100, -3.7e-250, 2e-300] {
+            let (mantissa, exponent) = float_exp(x);
+            assert!((1.0..2.0).contains(&mantissa.abs()));
+            let value = mantissa as f64 * 2f64.powf(exponent as f64);
+            assert!((value - x).abs() <= 1e-6 * x.abs(), "{}", x);
+        }
+    }
+}
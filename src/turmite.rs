@@ -0,0 +1,458 @@
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+use crate::game_of_life::{Automaton, Cell, ColorScheme, Engine, EngineKind, GameOfLife, Rule, Topology};
+
+/// `(rows, columns)` of a step north, east, south and west.
+const DIRECTIONS: [(i64, i64); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    Straight,
+    Right,
+    Back,
+    Left,
+}
+
+impl Turn {
+    /// Golly's codes: 1 for no turn, 2 for right, 4 for a U-turn and 8 for left.
+    fn from_code(code: u32) -> Result<Turn, String> {
+        match code {
+            1 => Ok(Turn::Straight),
+            2 => Ok(Turn::Right),
+            4 => Ok(Turn::Back),
+            8 => Ok(Turn::Left),
+            _ => Err(format!("invalid turn `{}`, expected 1, 2, 4 or 8", code)),
+        }
+    }
+
+    fn from_letter(letter: char) -> Result<Turn, String> {
+        match letter.to_ascii_uppercase() {
+            'N' => Ok(Turn::Straight),
+            'R' => Ok(Turn::Right),
+            'U' => Ok(Turn::Back),
+            'L' => Ok(Turn::Left),
+            _ => Err(format!("invalid turn `{}`, expected `L`, `R`, `N` or `U`", letter)),
+        }
+    }
+
+    /// Quarter turns clockwise, left and right are swapped for mirrored ants.
+    fn quarter_turns(self, mirrored: bool) -> usize {
+        match (self, mirrored) {
+            (Turn::Straight, _) => 0,
+            (Turn::Right, false) | (Turn::Left, true) => 1,
+            (Turn::Back, _) => 2,
+            (Turn::Left, false) | (Turn::Right, true) => 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    pub color: u8,
+    pub turn: Turn,
+    pub state: usize,
+}
+
+/// The rule of a turmite, see https://conwaylife.com/wiki/Turmite
+///
+/// An ant in state `s` on a cell of color `c` follows `transitions[s][c]`: it paints the cell, turns and
+/// moves forward one cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurmiteRule {
+    pub colors: u8,
+    pub transitions: Vec<Vec<Transition>>,
+    source: String,
+}
+
+impl FromStr for TurmiteRule {
+    type Err = String;
+
+    /// Parses a Langton's ant rule like `RL` or `LLRR`, a turn per color, or a transition table in
+    /// Golly's format like `{{{1, 2, 0}, {0, 8, 0}}}`, a `{color, turn, state}` for every state and color.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let transitions = if s.starts_with('{') {
+            parse_table(s)?
+        } else {
+            let colors = s.chars().count();
+            if !(2..=255).contains(&colors) {
+                return Err(format!("a turmite needs 2 to 255 colors, got `{}`", s));
+            }
+            vec![s
+                .chars()
+                .enumerate()
+                .map(|(color, letter)| {
+                    Ok(Transition {
+                        color: ((color + 1) % colors) as u8,
+                        turn: Turn::from_letter(letter)?,
+                        state: 0,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?]
+        };
+        Ok(TurmiteRule {
+            colors: transitions[0].len() as u8,
+            transitions,
+            source: s.to_string(),
+        })
+    }
+}
+
+/// The transitions of a table with the same number of colors for every state, and only colors and
+/// states that exist.
+fn parse_table(s: &str) -> Result<Vec<Vec<Transition>>, String> {
+    let invalid = |reason: &str| format!("invalid turmite table `{}`: {}", s, reason);
+    let mut chars = s.chars().filter(|c| !c.is_whitespace()).peekable();
+    let table = parse_list(&mut chars).map_err(|reason| invalid(&reason))?;
+    if chars.next().is_some() {
+        return Err(invalid("unexpected characters after the table"));
+    }
+
+    let states = table.as_list().ok_or_else(|| invalid("expected a list of states"))?;
+    let colors = states.first().and_then(Value::as_list).map_or(0, |colors| colors.len());
+    if !(2..=255).contains(&colors) {
+        return Err(invalid("expected 2 to 255 colors"));
+    }
+    states
+        .iter()
+        .map(|state| {
+            let transitions = state
+                .as_list()
+                .filter(|transitions| transitions.len() == colors)
+                .ok_or_else(|| invalid("every state needs a transition for every color"))?;
+            transitions
+                .iter()
+                .map(|transition| match transition.as_list() {
+                    Some([Value::Number(color), Value::Number(turn), Value::Number(state)])
+                        if (*color as usize) < colors && (*state as usize) < states.len() =>
+                    {
+                        Ok(Transition {
+                            color: *color as u8,
+                            turn: Turn::from_code(*turn).map_err(|reason| invalid(&reason))?,
+                            state: *state as usize,
+                        })
+                    }
+                    _ => Err(invalid(
+                        "expected transitions of an existing color, a turn and an existing state",
+                    )),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+enum Value {
+    Number(u32),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(values) => Some(values),
+            Value::Number(_) => None,
+        }
+    }
+}
+
+fn parse_list(chars: &mut std::iter::Peekable<impl Iterator<Item = char>>) -> Result<Value, String> {
+    if chars.next() != Some('{') {
+        return Err(String::from("expected `{`"));
+    }
+    let mut values = vec![];
+    loop {
+        let value = match chars.peek() {
+            Some('{') => parse_list(chars)?,
+            Some(c) if c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(digit) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    number.push(*digit);
+                    chars.next();
+                }
+                Value::Number(number.parse().map_err(|_| format!("invalid number `{}`", number))?)
+            }
+            _ => return Err(String::from("expected a number or `{`")),
+        };
+        values.push(value);
+        match chars.next() {
+            Some(',') => {}
+            Some('}') => return Ok(Value::List(values)),
+            _ => return Err(String::from("expected `,` or `}`")),
+        }
+    }
+}
+
+impl fmt::Display for TurmiteRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// The colors of the cells are the states of the grid, they only change under an ant.
+impl Automaton for TurmiteRule {
+    fn states(&self) -> u8 {
+        self.colors
+    }
+
+    fn next(&self, state: u8, _neighbors: &[u8; 8]) -> u8 {
+        state
+    }
+
+    /// Color 0 is the dead color of the default color scheme, the other colors go through its gradient.
+    fn colors(&self) -> Vec<[u8; 3]> {
+        let palette = ColorScheme::default().palette();
+        let last = self.colors as usize - 1;
+        (0..=last)
+            .map(|color| match color {
+                0 => palette[0],
+                color => palette[1 + 254 * color / last],
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ant {
+    pub row: i64,
+    pub column: i64,
+    /// 0 for north, 1 for east, 2 for south and 3 for west.
+    pub direction: usize,
+    pub state: usize,
+    /// Left and right are swapped after crossing a twisted edge, like the edges of a Klein bottle.
+    pub mirrored: bool,
+}
+
+impl Ant {
+    pub fn new(row: i64, column: i64, direction: usize) -> Ant {
+        Ant {
+            row,
+            column,
+            direction,
+            state: 0,
+            mirrored: false,
+        }
+    }
+}
+
+/// Turmites on a finite grid with edges glued according to its topology, the cells are colors.
+///
+/// Every generation moves every ant one step, one after another, ants that walk off a plane are gone.
+#[derive(Clone)]
+pub struct Turmites {
+    pub width: u32,
+    pub height: u32,
+    cells: Vec<u8>,
+    pub ants: Vec<Ant>,
+    rule: Rc<TurmiteRule>,
+    topology: Topology,
+    pub generation: u64,
+}
+
+impl Turmites {
+    /// A `width x height` grid of color 0 with `ants` ants facing north, evenly spaced along the middle
+    /// row.
+    pub fn new(width: u32, height: u32, rule: TurmiteRule, ants: u32) -> Result<Turmites, String> {
+        if width == 0 || height == 0 {
+            return Err(format!(
+                "turmites need a grid of at least 1x1, got {}x{}",
+                width, height
+            ));
+        }
+        let row = height as i64 / 2;
+        Ok(Turmites {
+            width,
+            height,
+            cells: vec![0; width as usize * height as usize],
+            ants: (0..ants as i64)
+                .map(|i| Ant::new(row, (i + 1) * width as i64 / (ants as i64 + 1), 0))
+                .collect(),
+            rule: Rc::new(rule),
+            topology: Topology::default(),
+            generation: 0,
+        })
+    }
+
+    pub fn tick(&mut self) {
+        let mut ants = std::mem::take(&mut self.ants);
+        ants.retain_mut(|ant| self.step(ant));
+        self.ants = ants;
+        self.generation += 1;
+    }
+
+    /// Moves an ant, returns whether it is still on the grid.
+    fn step(&mut self, ant: &mut Ant) -> bool {
+        let index = ant.row as usize * self.width as usize + ant.column as usize;
+        let transition = self.rule.transitions[ant.state][self.cells[index] as usize];
+        self.cells[index] = transition.color;
+        ant.state = transition.state;
+        ant.direction = (ant.direction + transition.turn.quarter_turns(ant.mirrored)) % 4;
+
+        let (delta_row, delta_column) = DIRECTIONS[ant.direction];
+        let (row, column) = (ant.row + delta_row, ant.column + delta_column);
+        if self.contains(row, column) {
+            ant.row = row;
+            ant.column = column;
+            return true;
+        }
+        let (next_row, next_column) = match self.topology.wrap(self.width, self.height, row, column) {
+            Some((row, column)) => (row as i64, column as i64),
+            None => return false,
+        };
+        // The ant leaves the new cell towards the cell it came from in the opposite direction
+        let back = (0..4).find(|direction| {
+            let (delta_row, delta_column) = DIRECTIONS[*direction];
+            let (row, column) = (next_row + delta_row, next_column + delta_column);
+            let cell = if self.contains(row, column) {
+                Some((row as u32, column as u32))
+            } else {
+                self.topology.wrap(self.width, self.height, row, column)
+            };
+            cell == Some((ant.row as u32, ant.column as u32))
+        });
+        ant.direction = back.map_or(ant.direction, |back| (back + 2) % 4);
+        let twisted = match self.topology {
+            Topology::Plane | Topology::Torus => false,
+            Topology::KleinBottle => delta_row != 0,
+            Topology::CrossSurface | Topology::Sphere => true,
+        };
+        ant.mirrored ^= twisted;
+        ant.row = next_row;
+        ant.column = next_column;
+        true
+    }
+
+    fn contains(&self, row: i64, column: i64) -> bool {
+        row >= 0 && column >= 0 && row < self.height as i64 && column < self.width as i64
+    }
+}
+
+impl Engine for Turmites {
+    /// Turmites do not follow a Life-like rule, this is Conway's rule.
+    fn rule(&self) -> Rule {
+        Rule::default()
+    }
+
+    fn set_rule(&mut self, _rule: Rule) -> Result<(), String> {
+        Err(String::from("turmites do not follow Life-like rules"))
+    }
+
+    fn automaton(&self) -> Rc<dyn Automaton> {
+        self.rule.clone()
+    }
+
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        if topology == Topology::Sphere && self.width != self.height {
+            return Err(format!(
+                "a sphere needs a square grid, got {}x{}",
+                self.width, self.height
+            ));
+        }
+        self.topology = topology;
+        Ok(())
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    fn population(&self) -> u64 {
+        self.cells.iter().filter(|color| **color != 0).count() as u64
+    }
+
+    fn step_pow2(&mut self, k: u32) {
+        (0..1u64 << k).for_each(|_| self.tick());
+    }
+
+    fn cell(&self, row: i64, column: i64) -> Cell {
+        if self.state(row, column) == 0 {
+            Cell::Dead
+        } else {
+            Cell::Alive
+        }
+    }
+
+    fn set_cell(&mut self, row: i64, column: i64, cell: Cell) {
+        self.set_state(row, column, cell as u8);
+    }
+
+    fn state(&self, row: i64, column: i64) -> u8 {
+        if !self.contains(row, column) {
+            return 0;
+        }
+        self.cells[row as usize * self.width as usize + column as usize]
+    }
+
+    fn set_state(&mut self, row: i64, column: i64, state: u8) {
+        if self.contains(row, column) && state < self.rule.colors {
+            self.cells[row as usize * self.width as usize + column as usize] = state;
+        }
+    }
+
+    fn live_cells(&self) -> Vec<(i64, i64)> {
+        self.cell_states().into_iter().map(|(cell, _)| cell).collect()
+    }
+
+    fn cell_states(&self) -> Vec<((i64, i64), u8)> {
+        let width = self.width as usize;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, color)| **color != 0)
+            .map(|(i, color)| (((i / width) as i64, (i % width) as i64), *color))
+            .collect()
+    }
+
+    fn highlights(&self) -> Vec<(i64, i64)> {
+        self.ants.iter().map(|ant| (ant.row, ant.column)).collect()
+    }
+
+    fn snapshot(&self) -> Box<dyn Engine> {
+        Box::new(self.clone())
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.cells.len() + self.ants.len() * std::mem::size_of::<Ant>()
+    }
+
+    fn bounds(&self) -> Option<(i64, i64, i64, i64)> {
+        Some((0, 0, self.height as i64 - 1, self.width as i64 - 1))
+    }
+
+    /// The colored cells and the ants.
+    fn bounding_box(&self) -> Option<(i64, i64, i64, i64)> {
+        self.cell_states()
+            .into_iter()
+            .map(|(cell, _)| cell)
+            .chain(self.highlights())
+            .fold(None, |bounding_box, (row, col)| match bounding_box {
+                None => Some((row, col, row, col)),
+                Some((top, left, bottom, right)) => {
+                    Some((top.min(row), left.min(col), bottom.max(row), right.max(col)))
+                }
+            })
+    }
+}
+
+/// Runs turmites on the Life canvas, `ants` ants on a `size x size` torus, see `TurmiteRule` for the
+/// rule.
+#[wasm_bindgen]
+pub fn turmite(
+    rule: &str,
+    ants: Option<u32>,
+    size: Option<u32>,
+    renderer: Option<String>,
+) -> Result<GameOfLife, JsValue> {
+    let size = size.unwrap_or(256);
+    let turmites = Turmites::new(size, size, rule.parse()?, ants.unwrap_or(1))?;
+    let game = GameOfLife::start(Box::new(turmites), EngineKind::Dense, renderer)?;
+    let last = size as i64 - 1;
+    game.canvas.borrow_mut().viewport.fit((0, 0, last, last));
+    Ok(game)
+}
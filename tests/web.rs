@@ -11,3 +11,32 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn blinker_oscillates() {
+    let mut universe = wasm_sandbox::JsUniverse::new(5, 5);
+    universe.set_cells(&[2, 1, 2, 2, 2, 3]).unwrap();
+    universe.tick(1);
+    assert!(universe.is_alive(1, 2) && universe.is_alive(2, 2) && universe.is_alive(3, 2));
+    assert!(!universe.is_alive(2, 1) && !universe.is_alive(2, 3));
+    universe.tick(1);
+    assert!(universe.is_alive(2, 1) && universe.is_alive(2, 2) && universe.is_alive(2, 3));
+    assert_eq!(universe.generation(), 2.0);
+}
+
+#[wasm_bindgen_test]
+fn cells_ptr_reads_packed_rows() {
+    let mut universe = wasm_sandbox::JsUniverse::new(70, 2);
+    universe.toggle(1, 65).unwrap();
+    let words = unsafe { std::slice::from_raw_parts(universe.cells_ptr(), 2 * universe.words_per_row()) };
+    assert_eq!(words, &[0, 0, 0, 1 << 1]);
+}
+
+#[wasm_bindgen_test]
+fn cells_outside_are_rejected() {
+    let mut universe = wasm_sandbox::JsUniverse::new(5, 5);
+    assert!(universe.toggle(5, 0).is_err());
+    assert!(universe.set_cells(&[0, 0, 2, 5]).is_err());
+    assert!(!universe.is_alive(0, 0));
+    assert!(!universe.is_alive(5, 5));
+}
@@ -1,4 +1,4 @@
-import { game_of_life, tracer, mendelbrot, threed } from "wasm-sandbox";
+import { game_of_life, turmite, elementary, larger_than_life, lenia, Census, tracer, mendelbrot, threed } from "wasm-sandbox";
 
 const params = new URLSearchParams(window.location.search)
 const canvasDiv = document.getElementById("canvas")
@@ -18,10 +18,153 @@ if (params.has('tracer')) {
   tracer()
 } else if (params.has('life')) {
   run()
-  game_of_life()
+  const life = game_of_life(params.get('engine'), params.get('renderer'))
+  if (params.has('rule')) {
+    life.set_rule(params.get('rule'))
+  }
+  if (['seed', 'density', 'symmetry'].some((param) => params.has(param))) {
+    if (!params.has('seed')) {
+      // Keep the seed in the URL, so that the soup can be shared
+      params.set('seed', String(Date.now()))
+      window.history.replaceState(null, '', '?' + params.toString())
+    }
+    life.soup(
+      params.get('seed'),
+      Number(params.get('density') || 0.5),
+      params.get('symmetry') || 'C1',
+      params.has('width') ? Number(params.get('width')) : undefined,
+      params.has('height') ? Number(params.get('height')) : undefined,
+    )
+  }
+  if (params.has('topology')) {
+    life.set_topology(params.get('topology'))
+  }
+  if (params.has('step')) {
+    life.set_step(Number(params.get('step')))
+  }
+  if (params.has('color')) {
+    life.set_color_mode(params.get('color'))
+  }
+  if (params.has('scheme')) {
+    life.set_color_scheme(params.get('scheme'))
+  }
+  if (params.has('history')) {
+    life.set_history_limit(Number(params.get('history')))
+  }
+  document.getElementById('timeline').style.display = 'block'
+  canvasDiv.addEventListener('dragover', (e) => e.preventDefault())
+  canvasDiv.addEventListener('drop', (e) => {
+    e.preventDefault()
+    const file = e.dataTransfer.files[0]
+    if (file) {
+      file.text().then((pattern) => life.load(pattern))
+    }
+  })
+  window.addEventListener('keydown', (e) => {
+    if (e.key === 's' && (e.ctrlKey || e.metaKey)) {
+      e.preventDefault()
+      const link = document.createElement('a')
+      link.href = URL.createObjectURL(new Blob([life.to_rle()], { type: 'text/plain' }))
+      link.download = 'pattern.rle'
+      link.click()
+    }
+  })
+} else if (params.has('turmite')) {
+  run()
+  const turmites = turmite(
+    params.get('rule') || 'RL',
+    params.has('ants') ? Number(params.get('ants')) : undefined,
+    params.has('size') ? Number(params.get('size')) : undefined,
+    params.get('renderer'),
+  )
+  if (params.has('topology')) {
+    turmites.set_topology(params.get('topology'))
+  }
+  if (params.has('step')) {
+    turmites.set_step(Number(params.get('step')))
+  }
+  if (params.has('highlight')) {
+    turmites.set_highlight(params.get('highlight') !== 'false')
+  }
+  if (params.has('steps')) {
+    turmites.fast_forward(Number(params.get('steps')))
+  }
+  document.getElementById('timeline').style.display = 'block'
+} else if (params.has('elementary')) {
+  run()
+  const automaton = elementary(
+    Number(params.get('rule') || 30),
+    params.get('seed'),
+    params.has('width') ? Number(params.get('width')) : undefined,
+    params.has('rows') ? Number(params.get('rows')) : undefined,
+    params.get('renderer'),
+  )
+  if (params.has('topology')) {
+    automaton.set_topology(params.get('topology'))
+  }
+  if (params.has('step')) {
+    automaton.set_step(Number(params.get('step')))
+  }
+  document.getElementById('timeline').style.display = 'block'
+} else if (params.has('ltl') || params.has('lenia')) {
+  run()
+  const start = params.has('ltl') ? larger_than_life : lenia
+  const automaton = start(
+    params.get('rule') || undefined,
+    params.has('size') ? Number(params.get('size')) : undefined,
+    params.get('scheme') || undefined,
+  )
+  if (params.has('speed')) {
+    automaton.set_steps_per_frame(Number(params.get('speed')))
+  }
+} else if (params.has('census')) {
+  const census = new Census(params.get('rule') || 'B3/S23', params.get('symmetry') || 'C1', params.get('seed') || String(Date.now()))
+  const soups = Number(params.get('soups') || 1000)
+  const output = document.createElement('pre')
+  document.body.appendChild(output)
+  const search = () => {
+    census.search(Math.min(10, soups - census.soups()))
+    output.textContent = census.to_json()
+    if (census.soups() < soups) {
+      setTimeout(search)
+    } else {
+      const link = document.createElement('a')
+      link.href = URL.createObjectURL(new Blob([census.to_json()], { type: 'application/json' }))
+      link.download = 'census.json'
+      link.textContent = 'Download census.json'
+      document.body.insertBefore(link, output)
+    }
+  }
+  search()
 } else if (params.has('mendelbrot')) {
   run()
-  mendelbrot()
+  const fractal = mendelbrot(params.get('layout') || undefined, params.get('c') || undefined, params.get('path') || undefined, params.get('formula') || undefined)
+  if (params.has('coloring')) {
+    fractal.set_coloring(params.get('coloring'))
+  }
+  if (params.has('gradient')) {
+    fractal.set_gradient(params.get('gradient'))
+  }
+  if (params.has('cycle')) {
+    fractal.set_cycle_speed(Number(params.get('cycle')))
+  }
+  const gradient = document.getElementById('gradient')
+  gradient.value = fractal.gradient()
+  gradient.style.display = 'block'
+  // Typing stops must not trigger the shortcuts of the view
+  gradient.addEventListener('keydown', (e) => e.stopPropagation())
+  gradient.addEventListener('change', () => fractal.set_gradient(gradient.value))
+  canvasDiv.addEventListener('dragover', (e) => e.preventDefault())
+  canvasDiv.addEventListener('drop', (e) => {
+    e.preventDefault()
+    const file = e.dataTransfer.files[0]
+    if (file) {
+      file.text().then((text) => {
+        fractal.import_gradient(file.name, text)
+        gradient.value = fractal.gradient()
+      })
+    }
+  })
 } else if (params.has('3d')) {
   run()
   threed()